@@ -0,0 +1,210 @@
+use std::path::PathBuf;
+
+use clap::{Args, Subcommand, ValueEnum};
+use reqwest::Method;
+use serde_json::json;
+
+use crate::{
+    client::ZitadelClient,
+    commands::{
+        application::{handle_add_application, handle_regenerate_secret},
+        request::{handle_list, handle_request, handle_update_entity},
+    },
+    config::Config,
+    error::ZitadelCLIError,
+    payloads::application::{
+        ApiAppConfig, NewApiApp, NewOidcApp, NewSamlApp, OidcAppConfig, SamlAppConfig, UpdateApp,
+    },
+};
+
+#[derive(Args)]
+pub struct AddAppArgs {
+    /// The project the application belongs to
+    #[arg(long, required(true))]
+    project_id: String,
+    #[arg(short, long, required(true))]
+    file_path: PathBuf,
+    /// The path the client id and secret are written to, they are printed if missing
+    #[arg(short, long)]
+    output_file: Option<PathBuf>,
+}
+
+#[derive(Args)]
+pub struct UpdateAppArgs {
+    app_id: String,
+    /// The project the application belongs to
+    #[arg(long, required(true))]
+    project_id: String,
+    #[arg(short, long, required(true))]
+    file_path: PathBuf,
+}
+
+#[derive(Subcommand)]
+pub enum AppType {
+    /// Adds an OpenID Connect application
+    Oidc(AddAppArgs),
+    /// Adds an API application
+    Api(AddAppArgs),
+    /// Adds a SAML application
+    Saml(AddAppArgs),
+}
+
+#[derive(Subcommand)]
+pub enum AppUpdate {
+    /// Updates the name of an application
+    Name(UpdateAppArgs),
+    /// Updates the OpenID Connect configuration of an application
+    Oidc(UpdateAppArgs),
+    /// Updates the API configuration of an application
+    Api(UpdateAppArgs),
+    /// Updates the SAML configuration of an application
+    Saml(UpdateAppArgs),
+}
+
+#[derive(Clone, ValueEnum)]
+/// The application types that have a client secret
+pub enum SecretAppType {
+    Oidc,
+    Api,
+}
+
+#[derive(Subcommand)]
+pub enum AppCommand {
+    /// Adds an application to a project, the client id and secret are only shown once
+    Add {
+        #[command(subcommand)]
+        app_type: AppType,
+    },
+    /// Lists the applications of a project
+    List {
+        #[arg(long, required(true))]
+        project_id: String,
+    },
+    /// Gets an application
+    Get {
+        app_id: String,
+        #[arg(long, required(true))]
+        project_id: String,
+    },
+    /// Updates an application
+    Update {
+        #[command(subcommand)]
+        update: AppUpdate,
+    },
+    /// Deletes an application
+    Delete {
+        app_id: String,
+        #[arg(long, required(true))]
+        project_id: String,
+    },
+    /// Generates a new client secret, the previous one stops working
+    RegenerateSecret {
+        app_id: String,
+        #[arg(long, required(true))]
+        project_id: String,
+        #[arg(long, value_enum, required(true))]
+        app_type: SecretAppType,
+        /// The path the client id and secret are written to, they are printed if missing
+        #[arg(short, long)]
+        output_file: Option<PathBuf>,
+    },
+}
+
+impl AppCommand {
+    /// Runs the application command against the Zitadel API
+    pub(crate) async fn handle(&self, config: &Config) -> Result<(), ZitadelCLIError> {
+        let client = &ZitadelClient::new(config).await?;
+        match self {
+            AppCommand::Add { app_type } => match app_type {
+                AppType::Oidc(args) => add::<NewOidcApp>(config, client, args, "oidc").await,
+                AppType::Api(args) => add::<NewApiApp>(config, client, args, "api").await,
+                AppType::Saml(args) => add::<NewSamlApp>(config, client, args, "saml").await,
+            },
+            AppCommand::List { project_id } => {
+                let endpoint = format!("/management/v1/projects/{project_id}/apps/_search");
+                handle_list(config, client, &endpoint, json!({})).await
+            }
+            AppCommand::Get { app_id, project_id } => {
+                let endpoint = format!("/management/v1/projects/{project_id}/apps/{app_id}");
+                handle_request(config, client, Method::GET, &endpoint, None).await
+            }
+            AppCommand::Update { update } => match update {
+                AppUpdate::Name(args) => self::update::<UpdateApp>(config, client, args, "").await,
+                AppUpdate::Oidc(args) => {
+                    self::update::<OidcAppConfig>(config, client, args, "/oidc_config").await
+                }
+                AppUpdate::Api(args) => {
+                    self::update::<ApiAppConfig>(config, client, args, "/api_config").await
+                }
+                AppUpdate::Saml(args) => {
+                    self::update::<SamlAppConfig>(config, client, args, "/saml_config").await
+                }
+            },
+            AppCommand::Delete { app_id, project_id } => {
+                let endpoint = format!("/management/v1/projects/{project_id}/apps/{app_id}");
+                handle_request(config, client, Method::DELETE, &endpoint, None).await
+            }
+            AppCommand::RegenerateSecret {
+                app_id,
+                project_id,
+                app_type,
+                output_file,
+            } => {
+                let config_path = match app_type {
+                    SecretAppType::Oidc => "oidc_config",
+                    SecretAppType::Api => "api_config",
+                };
+                handle_regenerate_secret(
+                    config,
+                    client,
+                    project_id,
+                    app_id,
+                    config_path,
+                    output_file.as_deref(),
+                )
+                .await
+            }
+        }
+    }
+}
+
+/// Adds an application of the type `app_type` (`oidc`, `api` or `saml`)
+async fn add<T>(
+    config: &Config,
+    client: &ZitadelClient,
+    args: &AddAppArgs,
+    app_type: &str,
+) -> Result<(), ZitadelCLIError>
+where
+    T: serde::de::DeserializeOwned + serde::Serialize,
+{
+    let endpoint = format!(
+        "/management/v1/projects/{}/apps/{app_type}",
+        args.project_id
+    );
+    handle_add_application::<T>(
+        config,
+        client,
+        &args.file_path,
+        &endpoint,
+        args.output_file.as_deref(),
+    )
+    .await
+}
+
+/// Updates an application, `config_path` selects the configuration to update, the general fields if empty
+async fn update<T>(
+    config: &Config,
+    client: &ZitadelClient,
+    args: &UpdateAppArgs,
+    config_path: &str,
+) -> Result<(), ZitadelCLIError>
+where
+    T: serde::de::DeserializeOwned + serde::Serialize,
+{
+    let endpoint = format!(
+        "/management/v1/projects/{}/apps/{}{config_path}",
+        args.project_id, args.app_id
+    );
+    handle_update_entity::<T>(config, client, Method::PUT, &args.file_path, &endpoint).await
+}
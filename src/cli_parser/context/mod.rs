@@ -0,0 +1,29 @@
+use std::path::PathBuf;
+
+use clap::Subcommand;
+
+use crate::commands::flows::Flow;
+
+#[derive(Subcommand)]
+pub enum ContextCommand {
+    /// Lists the contexts in the format of `--output`, the current one has `current` set
+    List,
+    /// Sets the context used when `--context` isn't specified
+    Use { name: String },
+    /// Adds a context, `--issuer` is required and `--client-id`, `--scopes`, `--callback-server-address`, `--key-file-path`
+    /// and `--org` are stored in the context as well. The secrets are read from the `CLIENT_SECRET` and
+    /// `PERSONAL_ACCESS_TOKEN` environment variables so they don't end up in the shell history. The fields that aren't
+    /// stored are read from the environment variables each time the context is used
+    Add {
+        name: String,
+        #[arg(long, value_enum)]
+        flow: Option<Flow>,
+        #[arg(long)]
+        is_secure: Option<bool>,
+        /// The file the credentials are cached in, defaults to `~/.config/zitadel-cli/credentials/<name>.json`
+        #[arg(long)]
+        credentials_file_path: Option<PathBuf>,
+    },
+    /// Removes a context and its cached credentials
+    Remove { name: String },
+}
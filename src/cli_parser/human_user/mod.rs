@@ -1,11 +1,254 @@
 use std::path::PathBuf;
 
-use clap::Subcommand;
+use clap::{Subcommand, ValueEnum};
+use reqwest::Method;
+use serde_json::{json, Value};
+
+use crate::{
+    client::ZitadelClient,
+    commands::{
+        add::handle_add_entity,
+        import::{handle_import, ImportOptions, DEFAULT_CONCURRENCY},
+        request::{handle_list, handle_request, handle_update_entity},
+    },
+    config::Config,
+    error::ZitadelCLIError,
+    payloads::{
+        hashed_password::HashFormat,
+        user::{NewHumanUser, UpdateHumanUser},
+    },
+};
 
 #[derive(Subcommand)]
 pub enum HumanUserCommand {
+    /// Adds a human user
     Add {
         #[arg(short, long, required(true))]
         file_path: PathBuf,
     },
+    /// Adds the human users of a CSV or JSONL file, and writes the outcome of each row to a JSONL report
+    Import {
+        /// A `.csv` file with a header row, whose columns are the fields of the payload of `add` (e.g.
+        /// `profile.givenName`), or a `.jsonl` file with the payload of `add` on each line
+        #[arg(short, long, required(true))]
+        file: PathBuf,
+        /// Maps a CSV column to a field of the payload, e.g. `first_name=profile.givenName`,
+        /// the column is ignored if the field is empty
+        #[arg(long = "map", value_name = "COLUMN=FIELD")]
+        mappings: Vec<String>,
+        /// The number of users created at the same time
+        #[arg(long, default_value_t = DEFAULT_CONCURRENCY, value_parser = clap::value_parser!(u16).range(1..=64))]
+        concurrency: u16,
+        /// Adds the valid users even if some rows are invalid, and keeps going when a user is rejected
+        #[arg(long)]
+        continue_on_error: bool,
+        /// The report, `<file>.report.jsonl` if missing
+        #[arg(long)]
+        report: Option<PathBuf>,
+        /// The report of a previous import of the same file, the users it created are skipped
+        #[arg(long)]
+        resume: Option<PathBuf>,
+        /// Migrates the password hashes of `hashedPassword.hash`, every row must have a hash of this algorithm
+        #[arg(long, value_enum)]
+        hash_format: Option<HashFormat>,
+    },
+    /// Lists the human users matching all the specified filters
+    List {
+        #[arg(long)]
+        username: Option<String>,
+        #[arg(long)]
+        email: Option<String>,
+        /// The id of the organization the users belong to
+        #[arg(long)]
+        organization_id: Option<String>,
+        #[arg(long, value_enum)]
+        state: Option<UserState>,
+    },
+    /// Gets a user by id
+    Get { id: String },
+    /// Updates a human user, the fields missing from the payload are left unchanged
+    Update {
+        id: String,
+        #[arg(short, long, required(true))]
+        file_path: PathBuf,
+    },
+    /// Deletes a user
+    Delete { id: String },
+    /// Deactivates a user, it can't log in anymore until it is reactivated
+    Deactivate { id: String },
+    /// Reactivates a deactivated user
+    Reactivate { id: String },
+    /// Locks a user, it can't log in anymore until it is unlocked
+    Lock { id: String },
+    /// Unlocks a locked user
+    Unlock { id: String },
+}
+
+#[derive(Clone, ValueEnum)]
+pub enum UserState {
+    Active,
+    Inactive,
+    Deleted,
+    Locked,
+    Initial,
+}
+
+impl UserState {
+    /// Returns the name of the state in the Zitadel API
+    pub fn as_api_str(&self) -> &'static str {
+        match self {
+            UserState::Active => "USER_STATE_ACTIVE",
+            UserState::Inactive => "USER_STATE_INACTIVE",
+            UserState::Deleted => "USER_STATE_DELETED",
+            UserState::Locked => "USER_STATE_LOCKED",
+            UserState::Initial => "USER_STATE_INITIAL",
+        }
+    }
+}
+
+impl HumanUserCommand {
+    /// Runs the human user command against the Zitadel API
+    pub(crate) async fn handle(&self, config: &Config) -> Result<(), ZitadelCLIError> {
+        // The rows are checked before logging in, the import creates its own client
+        if let HumanUserCommand::Import {
+            file,
+            mappings,
+            concurrency,
+            continue_on_error,
+            report,
+            resume,
+            hash_format,
+        } = self
+        {
+            let options = ImportOptions {
+                mappings,
+                concurrency: usize::from(*concurrency),
+                continue_on_error: *continue_on_error,
+                report: report.as_deref(),
+                resume: resume.as_deref(),
+                hash_format: *hash_format,
+            };
+            return handle_import(config, file, &options).await;
+        }
+        let client = &ZitadelClient::new(config).await?;
+        match self {
+            HumanUserCommand::Import { .. } => unreachable!("the import is handled before"),
+            HumanUserCommand::Add { file_path } => {
+                handle_add_entity::<NewHumanUser>(config, client, file_path, "/v2/users/human")
+                    .await
+            }
+            HumanUserCommand::List {
+                username,
+                email,
+                organization_id,
+                state,
+            } => {
+                let body = search_body(
+                    username.as_deref(),
+                    email.as_deref(),
+                    organization_id.as_deref(),
+                    state.as_ref(),
+                );
+                handle_list(config, client, "/v2/users", body).await
+            }
+            HumanUserCommand::Get { id } => {
+                handle_request(
+                    config,
+                    client,
+                    Method::GET,
+                    &format!("/v2/users/{id}"),
+                    None,
+                )
+                .await
+            }
+            HumanUserCommand::Update { id, file_path } => {
+                handle_update_entity::<UpdateHumanUser>(
+                    config,
+                    client,
+                    Method::PUT,
+                    file_path,
+                    &format!("/v2/users/human/{id}"),
+                )
+                .await
+            }
+            HumanUserCommand::Delete { id } => {
+                handle_request(
+                    config,
+                    client,
+                    Method::DELETE,
+                    &format!("/v2/users/{id}"),
+                    None,
+                )
+                .await
+            }
+            HumanUserCommand::Deactivate { id } => {
+                state_transition(config, client, id, "deactivate").await
+            }
+            HumanUserCommand::Reactivate { id } => {
+                state_transition(config, client, id, "reactivate").await
+            }
+            HumanUserCommand::Lock { id } => state_transition(config, client, id, "lock").await,
+            HumanUserCommand::Unlock { id } => state_transition(config, client, id, "unlock").await,
+        }
+    }
+}
+
+/// Calls the endpoint changing the state of the user `id`, e.g. `/v2/users/{id}/lock`
+async fn state_transition(
+    config: &Config,
+    client: &ZitadelClient,
+    id: &str,
+    transition: &str,
+) -> Result<(), ZitadelCLIError> {
+    let endpoint = format!("/v2/users/{id}/{transition}");
+    handle_request(config, client, Method::POST, &endpoint, Some(json!({}))).await
+}
+
+/// Builds the body of the [user search](https://zitadel.com/docs/apis/resources/user_service_v2/user-service-list-users),
+/// only human users matching all the specified filters are returned
+fn search_body(
+    username: Option<&str>,
+    email: Option<&str>,
+    organization_id: Option<&str>,
+    state: Option<&UserState>,
+) -> Value {
+    let mut queries = vec![json!({ "typeQuery": { "type": "TYPE_HUMAN" } })];
+    if let Some(username) = username {
+        queries.push(json!({
+            "userNameQuery": { "userName": username, "method": "TEXT_QUERY_METHOD_EQUALS_IGNORE_CASE" }
+        }));
+    }
+    if let Some(email) = email {
+        queries.push(json!({
+            "emailQuery": { "emailAddress": email, "method": "TEXT_QUERY_METHOD_EQUALS_IGNORE_CASE" }
+        }));
+    }
+    if let Some(organization_id) = organization_id {
+        queries.push(json!({ "organizationIdQuery": { "organizationId": organization_id } }));
+    }
+    if let Some(state) = state {
+        queries.push(json!({ "stateQuery": { "state": state.as_api_str() } }));
+    }
+    json!({ "queries": queries })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_search_body() {
+        let body = search_body(Some("minnie"), None, Some("123"), Some(&UserState::Locked));
+        assert_eq!(
+            body,
+            json!({
+                "queries": [
+                    { "typeQuery": { "type": "TYPE_HUMAN" } },
+                    { "userNameQuery": { "userName": "minnie", "method": "TEXT_QUERY_METHOD_EQUALS_IGNORE_CASE" } },
+                    { "organizationIdQuery": { "organizationId": "123" } },
+                    { "stateQuery": { "state": "USER_STATE_LOCKED" } }
+                ]
+            })
+        );
+    }
 }
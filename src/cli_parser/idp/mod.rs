@@ -1,19 +1,189 @@
 use std::path::PathBuf;
 
-use clap::Subcommand;
+use clap::{Args, Subcommand};
+use reqwest::Method;
+use serde_json::{json, Value};
 
-#[derive(Subcommand)]
-pub enum IdpProvider {
-    Google {
-        #[arg(short, long, required(true))]
-        file_path: PathBuf,
+use crate::{
+    client::ZitadelClient,
+    commands::{
+        add::handle_add_payloads,
+        request::{handle_list, handle_request},
     },
+    config::Config,
+    error::ZitadelCLIError,
+    payloads::idp::IdpType,
+};
+
+#[derive(Args)]
+pub struct AddIdpArgs {
+    #[arg(short, long, required(true))]
+    file_path: PathBuf,
+}
+
+#[derive(Args)]
+pub struct UpdateIdpArgs {
+    idp_id: String,
+    #[arg(short, long, required(true))]
+    file_path: PathBuf,
+}
+
+/// The identity provider templates, `A` holds the arguments of the `add` or `update` command
+#[derive(Subcommand)]
+pub enum IdpProvider<A: Args> {
+    /// Google
+    Google(A),
+    /// Generic OpenID Connect provider
+    GenericOidc(A),
+    /// Generic OAuth 2.0 provider
+    GenericOauth(A),
+    /// JWT provider
+    Jwt(A),
+    /// Azure AD
+    AzureAd(A),
+    /// GitHub
+    Github(A),
+    /// GitHub Enterprise Server
+    GithubEnterprise(A),
+    /// GitLab
+    Gitlab(A),
+    /// Self-hosted GitLab
+    GitlabSelfHosted(A),
+    /// LDAP
+    Ldap(A),
+    /// SAML
+    Saml(A),
+    /// Apple
+    Apple(A),
+}
+
+impl<A: Args> IdpProvider<A> {
+    /// Returns the arguments of the command and the template of the provider
+    fn args_and_type(&self) -> (&A, IdpType) {
+        match self {
+            IdpProvider::Google(args) => (args, IdpType::Google),
+            IdpProvider::GenericOidc(args) => (args, IdpType::GenericOidc),
+            IdpProvider::GenericOauth(args) => (args, IdpType::GenericOauth),
+            IdpProvider::Jwt(args) => (args, IdpType::Jwt),
+            IdpProvider::AzureAd(args) => (args, IdpType::AzureAd),
+            IdpProvider::Github(args) => (args, IdpType::Github),
+            IdpProvider::GithubEnterprise(args) => (args, IdpType::GithubEnterprise),
+            IdpProvider::Gitlab(args) => (args, IdpType::Gitlab),
+            IdpProvider::GitlabSelfHosted(args) => (args, IdpType::GitlabSelfHosted),
+            IdpProvider::Ldap(args) => (args, IdpType::Ldap),
+            IdpProvider::Saml(args) => (args, IdpType::Saml),
+            IdpProvider::Apple(args) => (args, IdpType::Apple),
+        }
+    }
 }
 
 #[derive(Subcommand)]
 pub enum IdpCommand {
+    /// Adds an identity provider to the organization
     Add {
         #[command(subcommand)]
-        provider: IdpProvider,
+        provider: IdpProvider<AddIdpArgs>,
+    },
+    /// Updates an identity provider of the organization, the provider must match its template
+    Update {
+        #[command(subcommand)]
+        provider: IdpProvider<UpdateIdpArgs>,
     },
+    /// Lists the identity providers of the organization
+    List,
+    /// Gets an identity provider
+    Get { idp_id: String },
+    /// Deletes an identity provider
+    Delete { idp_id: String },
+    /// Adds an identity provider to the login policy so it shows up on the login screen
+    Activate {
+        idp_id: String,
+        /// Targets the default login policy of the instance instead of the one of the organization
+        #[arg(long)]
+        instance: bool,
+    },
+    /// Removes an identity provider from the login policy
+    Deactivate {
+        idp_id: String,
+        /// Targets the default login policy of the instance instead of the one of the organization
+        #[arg(long)]
+        instance: bool,
+    },
+}
+
+impl IdpCommand {
+    /// Runs the identity provider command against the Zitadel API
+    pub(crate) async fn handle(&self, config: &Config) -> Result<(), ZitadelCLIError> {
+        let client = &ZitadelClient::new(config).await?;
+        match self {
+            IdpCommand::Add { provider } => {
+                let (args, idp_type) = provider.args_and_type();
+                let endpoint = format!("/management/v1/idps/{}", idp_type.path());
+                let payloads = idp_type.load_payloads(&args.file_path, config.payload_format)?;
+                handle_add_payloads(config, client, payloads, &endpoint).await
+            }
+            IdpCommand::Update { provider } => {
+                let (args, idp_type) = provider.args_and_type();
+                let endpoint = format!("/management/v1/idps/{}/{}", idp_type.path(), args.idp_id);
+                let body = idp_type.load_payload(&args.file_path, config.payload_format)?;
+                handle_request(config, client, Method::PUT, &endpoint, Some(body)).await
+            }
+            IdpCommand::List => {
+                let endpoint = "/management/v1/idps/templates/_search";
+                handle_list(config, client, endpoint, json!({})).await
+            }
+            IdpCommand::Get { idp_id } => {
+                let endpoint = format!("/management/v1/idps/templates/{idp_id}");
+                handle_request(config, client, Method::GET, &endpoint, None).await
+            }
+            IdpCommand::Delete { idp_id } => {
+                let endpoint = format!("/management/v1/idps/{idp_id}");
+                handle_request(config, client, Method::DELETE, &endpoint, None).await
+            }
+            IdpCommand::Activate { idp_id, instance } => {
+                let endpoint = format!("{}/policies/login/idps", api_prefix(*instance));
+                let body = activate_body(idp_id, *instance);
+                handle_request(config, client, Method::POST, &endpoint, Some(body)).await
+            }
+            IdpCommand::Deactivate { idp_id, instance } => {
+                let endpoint = format!("{}/policies/login/idps/{idp_id}", api_prefix(*instance));
+                handle_request(config, client, Method::DELETE, &endpoint, None).await
+            }
+        }
+    }
+}
+
+/// Returns the API managing the login policy, the admin API for the instance and the management API for the organization
+fn api_prefix(instance: bool) -> &'static str {
+    match instance {
+        true => "/admin/v1",
+        false => "/management/v1",
+    }
+}
+
+/// Returns the body linking an identity provider to the login policy, owned by the instance with `--instance`
+/// and by the organization otherwise
+fn activate_body(idp_id: &str, instance: bool) -> Value {
+    let owner_type = match instance {
+        true => "IDP_OWNER_TYPE_SYSTEM",
+        false => "IDP_OWNER_TYPE_ORG",
+    };
+    json!({ "idpId": idp_id, "ownerType": owner_type })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_activate_body() {
+        assert_eq!(
+            activate_body("123", false),
+            json!({ "idpId": "123", "ownerType": "IDP_OWNER_TYPE_ORG" })
+        );
+        assert_eq!(
+            activate_body("123", true),
+            json!({ "idpId": "123", "ownerType": "IDP_OWNER_TYPE_SYSTEM" })
+        );
+    }
 }
@@ -0,0 +1,205 @@
+use std::path::PathBuf;
+
+use clap::Subcommand;
+use reqwest::Method;
+use serde_json::json;
+
+use crate::{
+    client::ZitadelClient,
+    commands::{
+        add::handle_add_entity,
+        machine_user::{handle_add_key, handle_add_personal_access_token, handle_generate_secret},
+        request::{handle_list, handle_request},
+    },
+    config::Config,
+    error::ZitadelCLIError,
+    payloads::user::NewMachineUser,
+};
+
+#[derive(Subcommand)]
+pub enum MachineUserCommand {
+    /// Adds a machine user
+    Add {
+        #[arg(short, long, required(true))]
+        file_path: PathBuf,
+    },
+    /// Lists the machine users
+    List {
+        #[arg(long)]
+        username: Option<String>,
+    },
+    /// Gets a user by id
+    Get { id: String },
+    /// Deletes a user
+    Delete { id: String },
+    /// Manages the JSON keys used by the `JWT Profile Flow`
+    Key {
+        #[command(subcommand)]
+        key_command: KeyCommand,
+    },
+    /// Manages the client secret used by the `Client Credential Flow`
+    Secret {
+        #[command(subcommand)]
+        secret_command: SecretCommand,
+    },
+    /// Manages the personal access tokens used by the `Personal Access Token Flow`
+    Pat {
+        #[command(subcommand)]
+        pat_command: PatCommand,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum KeyCommand {
+    /// Adds a JSON key and writes the key file to disk, it can't be retrieved afterwards
+    Add {
+        user_id: String,
+        /// The path the key file is written to
+        #[arg(short, long, required(true))]
+        output_file: PathBuf,
+        /// The expiration date in the RFC 3339 format (e.g. 2030-01-01T00:00:00Z), the key never expires if missing
+        #[arg(long)]
+        expiration_date: Option<String>,
+    },
+    /// Lists the keys of a machine user
+    List { user_id: String },
+    /// Removes a key
+    Remove { user_id: String, key_id: String },
+}
+
+#[derive(Subcommand)]
+pub enum SecretCommand {
+    /// Generates a new client secret, replacing the previous one. It can't be retrieved afterwards
+    Generate {
+        user_id: String,
+        /// The path the client id and secret are written to, they are printed if missing
+        #[arg(short, long)]
+        output_file: Option<PathBuf>,
+    },
+    /// Removes the client secret
+    Remove { user_id: String },
+}
+
+#[derive(Subcommand)]
+pub enum PatCommand {
+    /// Adds a personal access token, it can't be retrieved afterwards
+    Add {
+        user_id: String,
+        /// The path the token is written to, it is printed if missing
+        #[arg(short, long)]
+        output_file: Option<PathBuf>,
+        /// The expiration date in the RFC 3339 format (e.g. 2030-01-01T00:00:00Z), the token never expires if missing
+        #[arg(long)]
+        expiration_date: Option<String>,
+    },
+    /// Lists the personal access tokens of a machine user
+    List { user_id: String },
+    /// Removes a personal access token
+    Remove { user_id: String, token_id: String },
+}
+
+impl MachineUserCommand {
+    /// Runs the machine user command against the Zitadel API
+    pub(crate) async fn handle(&self, config: &Config) -> Result<(), ZitadelCLIError> {
+        let client = &ZitadelClient::new(config).await?;
+        match self {
+            MachineUserCommand::Add { file_path } => {
+                handle_add_entity::<NewMachineUser>(
+                    config,
+                    client,
+                    file_path,
+                    "/management/v1/users/machine",
+                )
+                .await
+            }
+            MachineUserCommand::List { username } => {
+                let mut queries = vec![json!({ "typeQuery": { "type": "TYPE_MACHINE" } })];
+                if let Some(username) = username {
+                    queries.push(json!({
+                        "userNameQuery": { "userName": username, "method": "TEXT_QUERY_METHOD_EQUALS_IGNORE_CASE" }
+                    }));
+                }
+                let body = json!({ "queries": queries });
+                handle_list(config, client, "/v2/users", body).await
+            }
+            MachineUserCommand::Get { id } => {
+                handle_request(
+                    config,
+                    client,
+                    Method::GET,
+                    &format!("/v2/users/{id}"),
+                    None,
+                )
+                .await
+            }
+            MachineUserCommand::Delete { id } => {
+                handle_request(
+                    config,
+                    client,
+                    Method::DELETE,
+                    &format!("/v2/users/{id}"),
+                    None,
+                )
+                .await
+            }
+            MachineUserCommand::Key { key_command } => match key_command {
+                KeyCommand::Add {
+                    user_id,
+                    output_file,
+                    expiration_date,
+                } => {
+                    handle_add_key(
+                        config,
+                        client,
+                        user_id,
+                        expiration_date.as_deref(),
+                        output_file,
+                    )
+                    .await
+                }
+                KeyCommand::List { user_id } => {
+                    let endpoint = format!("/management/v1/users/{user_id}/keys/_search");
+                    handle_list(config, client, &endpoint, json!({})).await
+                }
+                KeyCommand::Remove { user_id, key_id } => {
+                    let endpoint = format!("/management/v1/users/{user_id}/keys/{key_id}");
+                    handle_request(config, client, Method::DELETE, &endpoint, None).await
+                }
+            },
+            MachineUserCommand::Secret { secret_command } => match secret_command {
+                SecretCommand::Generate {
+                    user_id,
+                    output_file,
+                } => handle_generate_secret(config, client, user_id, output_file.as_deref()).await,
+                SecretCommand::Remove { user_id } => {
+                    let endpoint = format!("/management/v1/users/{user_id}/secret");
+                    handle_request(config, client, Method::DELETE, &endpoint, None).await
+                }
+            },
+            MachineUserCommand::Pat { pat_command } => match pat_command {
+                PatCommand::Add {
+                    user_id,
+                    output_file,
+                    expiration_date,
+                } => {
+                    handle_add_personal_access_token(
+                        config,
+                        client,
+                        user_id,
+                        expiration_date.as_deref(),
+                        output_file.as_deref(),
+                    )
+                    .await
+                }
+                PatCommand::List { user_id } => {
+                    let endpoint = format!("/management/v1/users/{user_id}/pats/_search");
+                    handle_list(config, client, &endpoint, json!({})).await
+                }
+                PatCommand::Remove { user_id, token_id } => {
+                    let endpoint = format!("/management/v1/users/{user_id}/pats/{token_id}");
+                    handle_request(config, client, Method::DELETE, &endpoint, None).await
+                }
+            },
+        }
+    }
+}
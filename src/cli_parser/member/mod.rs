@@ -0,0 +1,157 @@
+use clap::{Args, Subcommand};
+use reqwest::Method;
+use serde_json::json;
+
+use crate::{
+    client::ZitadelClient,
+    commands::request::{handle_list, handle_request},
+    config::Config,
+    error::ZitadelCLIError,
+};
+
+/// The scope of the members, the organization of `--org` if neither `--instance` nor `--project` is set
+#[derive(Args)]
+pub struct MemberScope {
+    /// Targets the members of the instance
+    #[arg(long, conflicts_with = "project")]
+    instance: bool,
+    /// Targets the members of the project
+    #[arg(long)]
+    project: Option<String>,
+}
+
+impl MemberScope {
+    /// Returns the endpoint of the members of the scope
+    fn members_endpoint(&self) -> String {
+        match (self.instance, &self.project) {
+            (true, _) => "/admin/v1/members".to_string(),
+            (false, Some(project_id)) => format!("/management/v1/projects/{project_id}/members"),
+            (false, None) => "/management/v1/orgs/me/members".to_string(),
+        }
+    }
+
+    /// Returns the endpoint listing the member roles available in the scope
+    fn roles_endpoint(&self) -> &'static str {
+        match (self.instance, &self.project) {
+            (true, _) => "/admin/v1/members/roles/_search",
+            (false, Some(_)) => "/management/v1/projects/members/roles/_search",
+            (false, None) => "/management/v1/orgs/members/roles/_search",
+        }
+    }
+}
+
+#[derive(Subcommand)]
+pub enum MemberCommand {
+    /// Makes a user a member with administrative roles, e.g. `ORG_OWNER` or `PROJECT_OWNER`
+    Add {
+        user_id: String,
+        /// The comma separated member roles, see `member roles list`
+        #[arg(long, value_delimiter = ',', required(true))]
+        roles: Vec<String>,
+        #[command(flatten)]
+        scope: MemberScope,
+    },
+    /// Lists the members
+    List {
+        #[command(flatten)]
+        scope: MemberScope,
+    },
+    /// Replaces the roles of a member
+    Update {
+        user_id: String,
+        /// The comma separated member roles, see `member roles list`
+        #[arg(long, value_delimiter = ',', required(true))]
+        roles: Vec<String>,
+        #[command(flatten)]
+        scope: MemberScope,
+    },
+    /// Removes a member
+    Remove {
+        user_id: String,
+        #[command(flatten)]
+        scope: MemberScope,
+    },
+    /// Shows the member roles available in each scope
+    Roles {
+        #[command(subcommand)]
+        roles_command: RolesCommand,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum RolesCommand {
+    /// Lists the member roles available in the scope
+    List {
+        #[command(flatten)]
+        scope: MemberScope,
+    },
+}
+
+impl MemberCommand {
+    /// Runs the member command against the Zitadel API
+    pub(crate) async fn handle(&self, config: &Config) -> Result<(), ZitadelCLIError> {
+        let client = &ZitadelClient::new(config).await?;
+        match self {
+            MemberCommand::Add {
+                user_id,
+                roles,
+                scope,
+            } => {
+                let body = json!({ "userId": user_id, "roles": roles });
+                handle_request(
+                    config,
+                    client,
+                    Method::POST,
+                    &scope.members_endpoint(),
+                    Some(body),
+                )
+                .await
+            }
+            MemberCommand::List { scope } => {
+                let endpoint = format!("{}/_search", scope.members_endpoint());
+                handle_list(config, client, &endpoint, json!({})).await
+            }
+            MemberCommand::Update {
+                user_id,
+                roles,
+                scope,
+            } => {
+                let endpoint = format!("{}/{user_id}", scope.members_endpoint());
+                let body = json!({ "roles": roles });
+                handle_request(config, client, Method::PUT, &endpoint, Some(body)).await
+            }
+            MemberCommand::Remove { user_id, scope } => {
+                let endpoint = format!("{}/{user_id}", scope.members_endpoint());
+                handle_request(config, client, Method::DELETE, &endpoint, None).await
+            }
+            MemberCommand::Roles { roles_command } => match roles_command {
+                RolesCommand::List { scope } => {
+                    let endpoint = scope.roles_endpoint();
+                    handle_request(config, client, Method::POST, endpoint, Some(json!({}))).await
+                }
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_members_endpoint() {
+        let scope = |instance, project: Option<&str>| MemberScope {
+            instance,
+            project: project.map(str::to_string),
+        };
+        assert_eq!(scope(true, None).members_endpoint(), "/admin/v1/members");
+        assert_eq!(
+            scope(false, Some("123")).members_endpoint(),
+            "/management/v1/projects/123/members"
+        );
+        assert_eq!(
+            scope(false, None).members_endpoint(),
+            "/management/v1/orgs/me/members"
+        );
+    }
+}
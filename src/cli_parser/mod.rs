@@ -1,20 +1,42 @@
+pub(crate) mod app;
+pub(crate) mod context;
 pub(crate) mod human_user;
 pub(crate) mod idp;
+pub(crate) mod machine_user;
+pub(crate) mod member;
 pub(crate) mod org;
 pub(crate) mod project;
+pub(crate) mod user_grant;
 
-use clap::{Parser, Subcommand};
+use std::path::PathBuf;
+
+use app::AppCommand;
+use clap::{Args, Parser, Subcommand};
+use context::ContextCommand;
 use human_user::HumanUserCommand;
 use idp::IdpCommand;
+use machine_user::MachineUserCommand;
+use member::MemberCommand;
 use org::OrgCommand;
 use project::ProjectCommand;
-use tracing::{error, info};
+use tracing::info;
+use user_grant::UserGrantCommand;
 
 use crate::{
-    commands::{add::handle_add_entity, flows::Flow},
+    client::{Pagination, SortOrder, DEFAULT_PAGE_SIZE},
+    commands::{
+        apply::handle_apply,
+        context::{add_context, list_contexts, remove_context, use_context},
+        export::handle_export,
+        flows::Flow,
+    },
+    config::Config,
+    context::{default_credentials_file_path, resolve, Context},
+    env,
+    error::ZitadelCLIError,
     logout,
-    payloads::user::NewHumanUser,
-    NewGoogleIdp, NewOrganization, NewProject,
+    output::{Output, OutputFormat},
+    payloads::PayloadFormat,
 };
 
 #[derive(Parser)]
@@ -23,6 +45,85 @@ use crate::{
 pub struct Cli {
     #[command(subcommand)]
     pub command: Commands,
+    /// The context to use instead of the current one
+    #[arg(long, global = true)]
+    pub context: Option<String>,
+    #[command(flatten)]
+    pub config: ConfigArgs,
+}
+
+#[derive(Args)]
+/// The configuration passed as flags, takes precedence over the context and the environment variables
+pub struct ConfigArgs {
+    /// The path where the login informations are saved, overrides `CONFIG_FILE_PATH`
+    #[arg(long, global = true)]
+    pub config_file_path: Option<PathBuf>,
+    /// The URL of the Zitadel instance, overrides `ISSUER`
+    #[arg(long, global = true)]
+    pub issuer: Option<String>,
+    /// Overrides `CLIENT_ID`
+    #[arg(long, global = true)]
+    pub client_id: Option<String>,
+    /// Overrides `SCOPES`
+    #[arg(long, global = true)]
+    pub scopes: Option<String>,
+    /// Overrides `CALLBACK_SERVER_ADDRESS`
+    #[arg(long, global = true)]
+    pub callback_server_address: Option<String>,
+    /// The path to the JSON key file of the service user, overrides `KEY_FILE_PATH`
+    #[arg(long, global = true)]
+    pub key_file_path: Option<PathBuf>,
+    /// The id or name of the organization to target, overrides `ORG`. The default organization is used if missing
+    #[arg(long, global = true)]
+    pub org: Option<String>,
+    /// The format the responses are printed in
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Json)]
+    pub output: OutputFormat,
+    /// The comma separated fields kept in each resource, nested fields are separated by dots (e.g. `details.id`)
+    #[arg(long, global = true, value_delimiter = ',')]
+    pub fields: Vec<String>,
+    /// The number of resources requested per page by the list commands
+    #[arg(long, global = true, default_value_t = DEFAULT_PAGE_SIZE, value_parser = clap::value_parser!(u32).range(1..=1000))]
+    pub page_size: u32,
+    /// The maximum number of resources listed, fetched over as many pages as needed. Only the first page is listed if neither `--limit` nor `--all` is set
+    #[arg(long, global = true)]
+    pub limit: Option<u64>,
+    /// Lists every resource, fetching all the pages
+    #[arg(long, global = true, conflicts_with = "limit")]
+    pub all: bool,
+    /// The order of the listed resources
+    #[arg(long, global = true, value_enum)]
+    pub sort: Option<SortOrder>,
+    /// The format of the payload files, detected from their extension if missing, JSON by default
+    #[arg(long, global = true, value_enum)]
+    pub format: Option<PayloadFormat>,
+}
+
+impl ConfigArgs {
+    /// Returns the configuration layer made of the flags
+    fn to_config(&self) -> Config {
+        Config {
+            config_file_path: self.config_file_path.clone(),
+            issuer: self.issuer.clone(),
+            client_id: self.client_id.clone(),
+            scopes: self.scopes.clone(),
+            callback_server_address: self.callback_server_address.clone(),
+            key_file_path: self.key_file_path.clone(),
+            org: self.org.clone(),
+            output: Output {
+                format: self.output,
+                fields: self.fields.clone(),
+            },
+            pagination: Pagination {
+                page_size: self.page_size,
+                limit: self.limit,
+                all: self.all,
+                sort: self.sort,
+            },
+            payload_format: self.format,
+            ..Default::default()
+        }
+    }
 }
 
 #[derive(Subcommand)]
@@ -33,17 +134,38 @@ pub enum Commands {
         /// Opens the browser automatically
         #[arg(short, long)]
         open: bool,
-        #[arg(short, long, value_enum, default_value = "client-credentials")]
-        flow: Flow,
+        /// The flow to log in with, defaults to the flow of the context or `client-credentials`
+        #[arg(short, long, value_enum)]
+        flow: Option<Flow>,
     },
     HumanUser {
         #[command(subcommand)]
         human_user_command: HumanUserCommand,
     },
+    /// Manages the machine (service) users and their credentials
+    MachineUser {
+        #[command(subcommand)]
+        machine_user_command: MachineUserCommand,
+    },
     Project {
         #[command(subcommand)]
         project_command: ProjectCommand,
     },
+    /// Manages the user grants, which give the roles of a project to a user
+    UserGrant {
+        #[command(subcommand)]
+        user_grant_command: UserGrantCommand,
+    },
+    /// Manages the members of the instance, organizations and projects with their administrative roles
+    Member {
+        #[command(subcommand)]
+        member_command: MemberCommand,
+    },
+    /// Manages the applications of the projects
+    App {
+        #[command(subcommand)]
+        app_command: AppCommand,
+    },
     Org {
         #[command(subcommand)]
         org_command: OrgCommand,
@@ -52,56 +174,121 @@ pub enum Commands {
         #[command(subcommand)]
         idp_command: IdpCommand,
     },
+    /// Creates, updates and with `--prune` deletes the organizations, identity providers, policies, actions,
+    /// projects, roles, applications, project grants and members to match the YAML manifests
+    Apply {
+        /// A YAML file or a directory of YAML files, each file can hold several manifests separated by `---`
+        #[arg(short, long, required(true))]
+        file_path: PathBuf,
+        /// Deletes the resources of the described organizations and projects that have no manifest
+        #[arg(long)]
+        prune: bool,
+        /// Only prints the plan
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Writes the configuration of the organization targeted by `--org` as YAML manifests that `apply` reproduces,
+    /// the secrets are replaced by `REDACTED`
+    Export {
+        /// The directory of the manifests, created if missing
+        #[arg(long, required(true))]
+        out: PathBuf,
+    },
     /// Logs the user out
     Logout {},
+    /// Manages the contexts, each context targets a Zitadel instance
+    Context {
+        #[command(subcommand)]
+        context_command: ContextCommand,
+    },
 }
 
-pub(crate) async fn parse_cli() {
+/// Parses the arguments and runs the command
+/// - Returns the error of the command, which `main` maps to the exit code of the process
+pub(crate) async fn parse_cli() -> Result<(), ZitadelCLIError> {
     let cli = Cli::parse();
+    if let Commands::Context { context_command } = &cli.command {
+        return handle_context_command(context_command, &cli.config);
+    }
+    let context = resolve(cli.context.as_deref())?;
+    let mut flags = cli.config.to_config();
+    if let Commands::Login { flow, .. } = &cli.command {
+        flags.flow = flow.clone();
+    }
+    let config = Config::load(context, cli.context.is_some(), flags);
     match &cli.command {
-        Commands::Login { open, flow } => {
-            let login_result = flow.clone().login(*open).await;
-            match login_result {
-                Ok(_) => (),
-                Err(error) => {
-                    info! {"Failed to log in: {error}"};
+        Commands::Login { open, .. } => config.flow().login(*open, &config).await,
+        Commands::Logout {} => {
+            match logout::logout(&config) {
+                Err(ZitadelCLIError::IO(error)) if error.kind() == std::io::ErrorKind::NotFound => {
                 }
+                result => result?,
             }
+            info! {"Successfully logged out"};
+            Ok(())
         }
-        Commands::Logout {} => match logout::logout() {
-            Ok(()) => {
-                info! {"Successfully logged out"}
-            }
-            Err(error) => match error.kind() {
-                std::io::ErrorKind::NotFound => {
-                    info! {"Successfully logged out"}
-                }
-                error => {
-                    error! {"An unexpected error occured: {error}"};
-                }
-            },
-        },
-        Commands::HumanUser { human_user_command } => match human_user_command {
-            HumanUserCommand::Add { file_path } => {
-                handle_add_entity::<NewHumanUser>(file_path, "/v2/users/human").await
-            }
-        },
-        Commands::Project { project_command } => match project_command {
-            ProjectCommand::Add { file_path } => {
-                handle_add_entity::<NewProject>(file_path, "/management/v1/projects").await
-            }
-        },
-        Commands::Org { org_command } => match org_command {
-            OrgCommand::Add { file_path } => {
-                handle_add_entity::<NewOrganization>(file_path, "/v2/organizations").await
-            }
-        },
-        Commands::Idp { idp_command } => match idp_command {
-            IdpCommand::Add { provider } => match provider {
-                idp::IdpProvider::Google { file_path } => {
-                    handle_add_entity::<NewGoogleIdp>(file_path, "/management/v1/idps/google").await
-                }
-            },
-        },
+        Commands::HumanUser { human_user_command } => human_user_command.handle(&config).await,
+        Commands::MachineUser {
+            machine_user_command,
+        } => machine_user_command.handle(&config).await,
+        Commands::Project { project_command } => project_command.handle(&config).await,
+        Commands::UserGrant { user_grant_command } => user_grant_command.handle(&config).await,
+        Commands::Member { member_command } => member_command.handle(&config).await,
+        Commands::App { app_command } => app_command.handle(&config).await,
+        Commands::Org { org_command } => org_command.handle(&config).await,
+        Commands::Idp { idp_command } => idp_command.handle(&config).await,
+        Commands::Apply {
+            file_path,
+            prune,
+            dry_run,
+        } => handle_apply(&config, file_path, *prune, *dry_run).await,
+        Commands::Export { out } => handle_export(&config, out).await,
+        Commands::Context { .. } => unreachable!("context commands are handled before"),
+    }
+}
+
+/// Handles the `context` subcommands, the issuer, client id, scopes, callback server address,
+/// key file path and organization of a new context are taken from the global flags, and its secrets from the
+/// environment variables
+fn handle_context_command(
+    context_command: &ContextCommand,
+    flags: &ConfigArgs,
+) -> Result<(), ZitadelCLIError> {
+    match context_command {
+        ContextCommand::List => list_contexts(&flags.to_config().output),
+        ContextCommand::Use { name } => use_context(name),
+        ContextCommand::Add {
+            name,
+            flow,
+            is_secure,
+            credentials_file_path,
+        } => {
+            let secrets = env::config();
+            // The name is checked even with a custom credentials file
+            let default_path = default_credentials_file_path(name)?;
+            let credentials_file_path = credentials_file_path.clone().unwrap_or(default_path);
+            let issuer = flags.issuer.clone().ok_or(ZitadelCLIError::Context(
+                "--issuer is required to add a context".to_string(),
+            ));
+            issuer.and_then(|issuer| {
+                add_context(
+                    name,
+                    Context {
+                        issuer,
+                        client_id: flags.client_id.clone(),
+                        client_secret: secrets.client_secret,
+                        flow: flow.clone(),
+                        scopes: flags.scopes.clone(),
+                        callback_server_address: flags.callback_server_address.clone(),
+                        is_secure: *is_secure,
+                        personal_access_token: secrets.personal_access_token,
+                        key_file_path: flags.key_file_path.clone(),
+                        org: flags.org.clone(),
+                        credentials_file_path,
+                    },
+                )
+            })
+        }
+        ContextCommand::Remove { name } => remove_context(name),
     }
 }
@@ -1,11 +1,212 @@
 use std::path::PathBuf;
 
+use base64::{engine::general_purpose, Engine};
 use clap::Subcommand;
+use reqwest::Method;
+use serde_json::json;
 
+use crate::{
+    client::ZitadelClient,
+    commands::{
+        add::handle_add_entity,
+        org::{handle_domain_validation_token, handle_get_metadata, DomainValidationType},
+        request::{handle_list, handle_request},
+    },
+    config::Config,
+    error::ZitadelCLIError,
+    payloads::organization::NewOrganization,
+};
+
+/// Every command except `add` and `list` targets the organization of `--org`, the default one if missing
 #[derive(Subcommand)]
 pub enum OrgCommand {
+    /// Adds an organization
     Add {
         #[arg(short, long, required(true))]
         file_path: PathBuf,
     },
+    /// Lists the organizations of the instance
+    List {
+        #[arg(long)]
+        name: Option<String>,
+    },
+    /// Gets the organization
+    Get,
+    /// Renames the organization
+    Rename {
+        #[arg(long, required(true))]
+        name: String,
+    },
+    /// Deactivates the organization, its users can't log in anymore
+    Deactivate,
+    /// Reactivates the deactivated organization
+    Reactivate,
+    /// Deletes the organization and all its resources
+    Delete,
+    /// Manages the domains of the organization
+    Domain {
+        #[command(subcommand)]
+        domain_command: DomainCommand,
+    },
+    /// Manages the metadata of the organization
+    Metadata {
+        #[command(subcommand)]
+        metadata_command: MetadataCommand,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum DomainCommand {
+    /// Adds a domain and prints the token proving its ownership
+    Add {
+        domain: String,
+        #[arg(long, value_enum, default_value = "dns")]
+        validation_type: DomainValidationType,
+    },
+    /// Lists the domains of the organization
+    List,
+    /// Generates a new validation token, when the previous one expired
+    Token {
+        domain: String,
+        #[arg(long, value_enum, default_value = "dns")]
+        validation_type: DomainValidationType,
+    },
+    /// Verifies the ownership of a domain once its validation token is published
+    Verify { domain: String },
+    /// Sets the primary domain, used as the suffix of the login names
+    SetPrimary { domain: String },
+    /// Removes a domain
+    Remove { domain: String },
+}
+
+#[derive(Subcommand)]
+pub enum MetadataCommand {
+    /// Sets the value of a metadata, it is created if missing
+    Set { key: String, value: String },
+    /// Lists the metadata of the organization
+    List,
+    /// Prints the value of a metadata
+    Get { key: String },
+    /// Removes a metadata
+    Remove { key: String },
+}
+
+impl OrgCommand {
+    /// Runs the organization command against the Zitadel API
+    pub(crate) async fn handle(&self, config: &Config) -> Result<(), ZitadelCLIError> {
+        let client = &ZitadelClient::new(config).await?;
+        match self {
+            OrgCommand::Add { file_path } => {
+                handle_add_entity::<NewOrganization>(config, client, file_path, "/v2/organizations")
+                    .await
+            }
+            OrgCommand::List { name } => {
+                let mut queries = vec![];
+                if let Some(name) = name {
+                    queries.push(json!({
+                        "nameQuery": { "name": name, "method": "TEXT_QUERY_METHOD_EQUALS_IGNORE_CASE" }
+                    }));
+                }
+                let body = json!({ "queries": queries });
+                let endpoint = "/v2/organizations/_search";
+                handle_list(config, client, endpoint, body).await
+            }
+            OrgCommand::Get => {
+                handle_request(config, client, Method::GET, "/management/v1/orgs/me", None).await
+            }
+            OrgCommand::Rename { name } => {
+                let body = json!({ "name": name });
+                handle_request(
+                    config,
+                    client,
+                    Method::PUT,
+                    "/management/v1/orgs/me",
+                    Some(body),
+                )
+                .await
+            }
+            OrgCommand::Deactivate => {
+                let endpoint = "/management/v1/orgs/me/_deactivate";
+                handle_request(config, client, Method::POST, endpoint, Some(json!({}))).await
+            }
+            OrgCommand::Reactivate => {
+                let endpoint = "/management/v1/orgs/me/_reactivate";
+                handle_request(config, client, Method::POST, endpoint, Some(json!({}))).await
+            }
+            OrgCommand::Delete => {
+                handle_request(
+                    config,
+                    client,
+                    Method::DELETE,
+                    "/management/v1/orgs/me",
+                    None,
+                )
+                .await
+            }
+            OrgCommand::Domain { domain_command } => domain_command.handle(config, client).await,
+            OrgCommand::Metadata { metadata_command } => {
+                metadata_command.handle(config, client).await
+            }
+        }
+    }
+}
+
+impl DomainCommand {
+    /// Runs the domain command against the Zitadel API
+    async fn handle(&self, config: &Config, client: &ZitadelClient) -> Result<(), ZitadelCLIError> {
+        match self {
+            DomainCommand::Add {
+                domain,
+                validation_type,
+            } => {
+                handle_domain_validation_token(config, client, domain, validation_type, true).await
+            }
+            DomainCommand::List => {
+                let endpoint = "/management/v1/orgs/me/domains/_search";
+                handle_list(config, client, endpoint, json!({})).await
+            }
+            DomainCommand::Token {
+                domain,
+                validation_type,
+            } => {
+                handle_domain_validation_token(config, client, domain, validation_type, false).await
+            }
+            DomainCommand::Verify { domain } => {
+                let endpoint =
+                    format!("/management/v1/orgs/me/domains/{domain}/validation/_validate");
+                handle_request(config, client, Method::POST, &endpoint, Some(json!({}))).await
+            }
+            DomainCommand::SetPrimary { domain } => {
+                let endpoint = "/management/v1/orgs/me/domains/_set_primary";
+                let body = json!({ "domain": domain });
+                handle_request(config, client, Method::POST, endpoint, Some(body)).await
+            }
+            DomainCommand::Remove { domain } => {
+                let endpoint = format!("/management/v1/orgs/me/domains/{domain}");
+                handle_request(config, client, Method::DELETE, &endpoint, None).await
+            }
+        }
+    }
+}
+
+impl MetadataCommand {
+    /// Runs the metadata command against the Zitadel API, the values are base64 encoded by the API
+    async fn handle(&self, config: &Config, client: &ZitadelClient) -> Result<(), ZitadelCLIError> {
+        match self {
+            MetadataCommand::Set { key, value } => {
+                let endpoint = format!("/management/v1/metadata/{key}");
+                let body = json!({ "value": general_purpose::STANDARD.encode(value) });
+                handle_request(config, client, Method::POST, &endpoint, Some(body)).await
+            }
+            MetadataCommand::List => {
+                let endpoint = "/management/v1/metadata/_search";
+                handle_list(config, client, endpoint, json!({})).await
+            }
+            MetadataCommand::Get { key } => handle_get_metadata(config, client, key).await,
+            MetadataCommand::Remove { key } => {
+                let endpoint = format!("/management/v1/metadata/{key}");
+                handle_request(config, client, Method::DELETE, &endpoint, None).await
+            }
+        }
+    }
 }
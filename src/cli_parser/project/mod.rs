@@ -1,11 +1,256 @@
 use std::path::PathBuf;
 
 use clap::Subcommand;
+use reqwest::Method;
+use serde_json::json;
+
+use crate::{
+    client::ZitadelClient,
+    commands::{
+        add::handle_add_entity,
+        request::{handle_list, handle_request, handle_update_entity},
+    },
+    config::Config,
+    error::ZitadelCLIError,
+    payloads::project::{BulkProjectRoles, NewProject},
+};
 
 #[derive(Subcommand)]
 pub enum ProjectCommand {
+    /// Adds a project
+    Add {
+        #[arg(short, long, required(true))]
+        file_path: PathBuf,
+    },
+    /// Lists the projects of the organization
+    List {
+        #[arg(long)]
+        name: Option<String>,
+    },
+    /// Gets a project
+    Get { project_id: String },
+    /// Updates the settings of a project
+    Update {
+        project_id: String,
+        #[arg(short, long, required(true))]
+        file_path: PathBuf,
+    },
+    /// Deletes a project, its applications, roles and grants
+    Delete { project_id: String },
+    /// Deactivates a project, its users can't log in to its applications anymore
+    Deactivate { project_id: String },
+    /// Reactivates a deactivated project
+    Reactivate { project_id: String },
+    /// Manages the roles of a project
+    Role {
+        #[command(subcommand)]
+        role_command: RoleCommand,
+    },
+    /// Manages the grants of a project to other organizations
+    Grant {
+        #[command(subcommand)]
+        grant_command: GrantCommand,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum RoleCommand {
+    /// Adds a role to a project
     Add {
+        project_id: String,
+        #[arg(long, required(true))]
+        key: String,
+        #[arg(long, required(true))]
+        display_name: String,
+        #[arg(long)]
+        group: Option<String>,
+    },
+    /// Adds the roles of `--file-path` to a project at once
+    BulkAdd {
+        project_id: String,
         #[arg(short, long, required(true))]
         file_path: PathBuf,
     },
+    /// Lists the roles of a project
+    List { project_id: String },
+    /// Updates the display name and group of a role, its key can't be changed
+    Update {
+        project_id: String,
+        key: String,
+        #[arg(long, required(true))]
+        display_name: String,
+        #[arg(long)]
+        group: Option<String>,
+    },
+    /// Removes a role from a project, it is also removed from the user and project grants
+    Remove { project_id: String, key: String },
+}
+
+#[derive(Subcommand)]
+pub enum GrantCommand {
+    /// Grants a project to another organization, which can then grant the roles to its users
+    Add {
+        project_id: String,
+        #[arg(long, required(true))]
+        granted_org_id: String,
+        /// The comma separated keys of the roles granted to the organization
+        #[arg(long, value_delimiter = ',', required(true))]
+        role_keys: Vec<String>,
+    },
+    /// Lists the grants of a project
+    List { project_id: String },
+    /// Replaces the roles granted to the organization
+    Update {
+        project_id: String,
+        grant_id: String,
+        /// The comma separated keys of the roles granted to the organization
+        #[arg(long, value_delimiter = ',', required(true))]
+        role_keys: Vec<String>,
+    },
+    /// Removes a grant, the organization loses access to the project
+    Remove {
+        project_id: String,
+        grant_id: String,
+    },
+}
+
+impl ProjectCommand {
+    /// Runs the project command against the Zitadel API
+    pub(crate) async fn handle(&self, config: &Config) -> Result<(), ZitadelCLIError> {
+        let client = &ZitadelClient::new(config).await?;
+        match self {
+            ProjectCommand::Add { file_path } => {
+                handle_add_entity::<NewProject>(
+                    config,
+                    client,
+                    file_path,
+                    "/management/v1/projects",
+                )
+                .await
+            }
+            ProjectCommand::List { name } => {
+                let mut queries = vec![];
+                if let Some(name) = name {
+                    queries.push(json!({
+                        "nameQuery": { "name": name, "method": "TEXT_QUERY_METHOD_EQUALS_IGNORE_CASE" }
+                    }));
+                }
+                let body = json!({ "queries": queries });
+                let endpoint = "/management/v1/projects/_search";
+                handle_list(config, client, endpoint, body).await
+            }
+            ProjectCommand::Get { project_id } => {
+                let endpoint = format!("/management/v1/projects/{project_id}");
+                handle_request(config, client, Method::GET, &endpoint, None).await
+            }
+            ProjectCommand::Update {
+                project_id,
+                file_path,
+            } => {
+                let endpoint = format!("/management/v1/projects/{project_id}");
+                handle_update_entity::<NewProject>(
+                    config,
+                    client,
+                    Method::PUT,
+                    file_path,
+                    &endpoint,
+                )
+                .await
+            }
+            ProjectCommand::Delete { project_id } => {
+                let endpoint = format!("/management/v1/projects/{project_id}");
+                handle_request(config, client, Method::DELETE, &endpoint, None).await
+            }
+            ProjectCommand::Deactivate { project_id } => {
+                let endpoint = format!("/management/v1/projects/{project_id}/_deactivate");
+                handle_request(config, client, Method::POST, &endpoint, Some(json!({}))).await
+            }
+            ProjectCommand::Reactivate { project_id } => {
+                let endpoint = format!("/management/v1/projects/{project_id}/_reactivate");
+                handle_request(config, client, Method::POST, &endpoint, Some(json!({}))).await
+            }
+            ProjectCommand::Role { role_command } => role_command.handle(config, client).await,
+            ProjectCommand::Grant { grant_command } => grant_command.handle(config, client).await,
+        }
+    }
+}
+
+impl RoleCommand {
+    /// Runs the project role command against the Zitadel API
+    async fn handle(&self, config: &Config, client: &ZitadelClient) -> Result<(), ZitadelCLIError> {
+        match self {
+            RoleCommand::Add {
+                project_id,
+                key,
+                display_name,
+                group,
+            } => {
+                let endpoint = format!("/management/v1/projects/{project_id}/roles");
+                let body = json!({ "roleKey": key, "displayName": display_name, "group": group });
+                handle_request(config, client, Method::POST, &endpoint, Some(body)).await
+            }
+            RoleCommand::BulkAdd {
+                project_id,
+                file_path,
+            } => {
+                let endpoint = format!("/management/v1/projects/{project_id}/roles/_bulk");
+                handle_add_entity::<BulkProjectRoles>(config, client, file_path, &endpoint).await
+            }
+            RoleCommand::List { project_id } => {
+                let endpoint = format!("/management/v1/projects/{project_id}/roles/_search");
+                handle_list(config, client, &endpoint, json!({})).await
+            }
+            RoleCommand::Update {
+                project_id,
+                key,
+                display_name,
+                group,
+            } => {
+                let endpoint = format!("/management/v1/projects/{project_id}/roles/{key}");
+                let body = json!({ "displayName": display_name, "group": group });
+                handle_request(config, client, Method::PUT, &endpoint, Some(body)).await
+            }
+            RoleCommand::Remove { project_id, key } => {
+                let endpoint = format!("/management/v1/projects/{project_id}/roles/{key}");
+                handle_request(config, client, Method::DELETE, &endpoint, None).await
+            }
+        }
+    }
+}
+
+impl GrantCommand {
+    /// Runs the project grant command against the Zitadel API
+    async fn handle(&self, config: &Config, client: &ZitadelClient) -> Result<(), ZitadelCLIError> {
+        match self {
+            GrantCommand::Add {
+                project_id,
+                granted_org_id,
+                role_keys,
+            } => {
+                let endpoint = format!("/management/v1/projects/{project_id}/grants");
+                let body = json!({ "grantedOrgId": granted_org_id, "roleKeys": role_keys });
+                handle_request(config, client, Method::POST, &endpoint, Some(body)).await
+            }
+            GrantCommand::List { project_id } => {
+                let endpoint = format!("/management/v1/projects/{project_id}/grants/_search");
+                handle_list(config, client, &endpoint, json!({})).await
+            }
+            GrantCommand::Update {
+                project_id,
+                grant_id,
+                role_keys,
+            } => {
+                let endpoint = format!("/management/v1/projects/{project_id}/grants/{grant_id}");
+                let body = json!({ "roleKeys": role_keys });
+                handle_request(config, client, Method::PUT, &endpoint, Some(body)).await
+            }
+            GrantCommand::Remove {
+                project_id,
+                grant_id,
+            } => {
+                let endpoint = format!("/management/v1/projects/{project_id}/grants/{grant_id}");
+                handle_request(config, client, Method::DELETE, &endpoint, None).await
+            }
+        }
+    }
 }
@@ -0,0 +1,127 @@
+use clap::Subcommand;
+use reqwest::Method;
+use serde_json::{json, Value};
+
+use crate::{
+    client::ZitadelClient,
+    commands::request::{handle_list, handle_request},
+    config::Config,
+    error::ZitadelCLIError,
+};
+
+#[derive(Subcommand)]
+pub enum UserGrantCommand {
+    /// Grants roles of a project to a user
+    Add {
+        #[arg(long, required(true))]
+        user: String,
+        #[arg(long, required(true))]
+        project: String,
+        /// The comma separated keys of the roles granted to the user
+        #[arg(long, value_delimiter = ',', required(true))]
+        roles: Vec<String>,
+        /// The project grant the roles come from, when the project is granted by another organization
+        #[arg(long)]
+        project_grant_id: Option<String>,
+    },
+    /// Lists the user grants, filtered by user, project and role
+    List {
+        #[arg(long)]
+        user: Option<String>,
+        #[arg(long)]
+        project: Option<String>,
+        #[arg(long)]
+        role: Option<String>,
+    },
+    /// Replaces the roles of a user grant
+    Update {
+        user_id: String,
+        grant_id: String,
+        /// The comma separated keys of the roles granted to the user
+        #[arg(long, value_delimiter = ',', required(true))]
+        roles: Vec<String>,
+    },
+    /// Removes a user grant, the user loses all its roles
+    Remove { user_id: String, grant_id: String },
+}
+
+impl UserGrantCommand {
+    /// Runs the user grant command against the Zitadel API
+    pub(crate) async fn handle(&self, config: &Config) -> Result<(), ZitadelCLIError> {
+        let client = &ZitadelClient::new(config).await?;
+        match self {
+            UserGrantCommand::Add {
+                user,
+                project,
+                roles,
+                project_grant_id,
+            } => {
+                let endpoint = format!("/management/v1/users/{user}/grants");
+                let body = json!({
+                    "projectId": project,
+                    "projectGrantId": project_grant_id,
+                    "roleKeys": roles,
+                });
+                handle_request(config, client, Method::POST, &endpoint, Some(body)).await
+            }
+            UserGrantCommand::List {
+                user,
+                project,
+                role,
+            } => {
+                let body = search_body(user.as_deref(), project.as_deref(), role.as_deref());
+                let endpoint = "/management/v1/users/grants/_search";
+                handle_list(config, client, endpoint, body).await
+            }
+            UserGrantCommand::Update {
+                user_id,
+                grant_id,
+                roles,
+            } => {
+                let endpoint = format!("/management/v1/users/{user_id}/grants/{grant_id}");
+                let body = json!({ "roleKeys": roles });
+                handle_request(config, client, Method::PUT, &endpoint, Some(body)).await
+            }
+            UserGrantCommand::Remove { user_id, grant_id } => {
+                let endpoint = format!("/management/v1/users/{user_id}/grants/{grant_id}");
+                handle_request(config, client, Method::DELETE, &endpoint, None).await
+            }
+        }
+    }
+}
+
+/// Builds the body of the user grant search, only the given filters are added
+fn search_body(user_id: Option<&str>, project_id: Option<&str>, role_key: Option<&str>) -> Value {
+    let mut queries = vec![];
+    if let Some(user_id) = user_id {
+        queries.push(json!({ "userIdQuery": { "userId": user_id } }));
+    }
+    if let Some(project_id) = project_id {
+        queries.push(json!({ "projectIdQuery": { "projectId": project_id } }));
+    }
+    if let Some(role_key) = role_key {
+        queries.push(json!({
+            "roleKeyQuery": { "roleKey": role_key, "method": "TEXT_QUERY_METHOD_EQUALS" }
+        }));
+    }
+    json!({ "queries": queries })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_search_body() {
+        let body = search_body(Some("123"), None, Some("editor"));
+        assert_eq!(
+            body,
+            json!({
+                "queries": [
+                    { "userIdQuery": { "userId": "123" } },
+                    { "roleKeyQuery": { "roleKey": "editor", "method": "TEXT_QUERY_METHOD_EQUALS" } }
+                ]
+            })
+        );
+    }
+}
@@ -0,0 +1,404 @@
+//! The HTTP client shared by every call to the Zitadel API
+//! It sets the user agent and the timeouts, and the [`ZitadelClient`] used by the resource commands also
+//! authenticates the requests, targets the organization and retries the requests rejected by a rate limit or an
+//! unavailable instance, and pages through the search endpoints
+
+use std::time::Duration;
+
+use clap::ValueEnum;
+use reqwest::{
+    header::{HeaderValue, RETRY_AFTER},
+    Client, Method, Response, StatusCode,
+};
+use serde_json::{json, Value};
+use tracing::warn;
+
+use crate::{
+    commands::{
+        load_valid_access_token,
+        org::{resolve_org_id, ORG_ID_HEADER},
+        API_KEYS,
+    },
+    config::Config,
+    error::{ApiError, ZitadelCLIError},
+};
+
+const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const READ_TIMEOUT: Duration = Duration::from_secs(30);
+/// The number of times a request is retried, after the first attempt
+const MAX_RETRIES: u32 = 4;
+/// The last segments of the endpoints generating a credential, e.g. `/management/v1/users/{id}/pats`
+const CREDENTIAL_SEGMENTS: [&str; 3] = ["secret", "keys", "pats"];
+/// The delay before the first retry, doubled for each following one
+const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+/// The longest delay accepted from a `Retry-After` header
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(60);
+/// The number of resources requested per page if `--page-size` is missing
+pub(crate) const DEFAULT_PAGE_SIZE: u32 = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+/// The order of the resources of a search, by the default sorting column of the endpoint
+pub(crate) enum SortOrder {
+    Asc,
+    Desc,
+}
+
+#[derive(Debug, Clone)]
+/// Which pages of a search are fetched
+pub(crate) struct Pagination {
+    /// The number of resources requested per page
+    pub(crate) page_size: u32,
+    /// The maximum number of resources fetched over all the pages
+    pub(crate) limit: Option<u64>,
+    /// Fetches every page, `limit` is ignored
+    pub(crate) all: bool,
+    /// The order of the resources, the API default if missing
+    pub(crate) sort: Option<SortOrder>,
+}
+
+impl Default for Pagination {
+    fn default() -> Self {
+        Pagination {
+            page_size: DEFAULT_PAGE_SIZE,
+            limit: None,
+            all: false,
+            sort: None,
+        }
+    }
+}
+
+impl Pagination {
+    /// Returns the limit of the page following the `fetched` resources, `None` once enough resources were fetched
+    /// Only the first page is fetched if neither `--all` nor `--limit` is set
+    fn next_limit(&self, fetched: u64) -> Option<u32> {
+        let page_size = self.page_size.max(1);
+        let remaining = match (self.all, self.limit) {
+            (true, _) => return Some(page_size),
+            (false, Some(limit)) => limit.saturating_sub(fetched),
+            (false, None) => u64::from(page_size).saturating_sub(fetched),
+        };
+        (remaining > 0).then(|| remaining.min(u64::from(page_size)) as u32)
+    }
+
+    /// Returns the `query` of the search body requesting the page starting at `offset`
+    fn query(&self, offset: u64, limit: u32) -> Value {
+        let mut query = json!({ "offset": offset.to_string(), "limit": limit });
+        if let Some(sort) = self.sort {
+            query["asc"] = Value::Bool(sort == SortOrder::Asc);
+        }
+        query
+    }
+}
+
+/// Returns an HTTP client with the user agent and timeouts of the CLI, used by the login flows
+pub(crate) fn http_client() -> Result<Client, ZitadelCLIError> {
+    Ok(Client::builder()
+        .user_agent(USER_AGENT)
+        .connect_timeout(CONNECT_TIMEOUT)
+        .read_timeout(READ_TIMEOUT)
+        .build()?)
+}
+
+#[derive(Clone)]
+/// An authenticated client of the Zitadel API
+pub(crate) struct ZitadelClient {
+    http: Client,
+    /// The url of the Zitadel instance, the endpoints are relative to it
+    base_url: String,
+    access_token: String,
+    /// The organization sent in the `x-zitadel-orgid` header, the default organization is used if missing
+    org_id: Option<String>,
+}
+
+impl ZitadelClient {
+    /// Creates a client with the access token of the configuration, refreshed if it expired,
+    /// and resolves the organization targeted by `--org`
+    /// - Returns `Err(ZitadelCLIError::Config(errors))` if the configuration keys needed by the API are missing
+    pub(crate) async fn new(config: &Config) -> Result<ZitadelClient, ZitadelCLIError> {
+        config.validate(API_KEYS)?;
+        let token = load_valid_access_token(config).await?;
+        let mut client = ZitadelClient {
+            http: http_client()?,
+            base_url: config.issuer()?,
+            access_token: token.access_token,
+            org_id: None,
+        };
+        if let Some(org) = &config.org {
+            client.org_id = Some(resolve_org_id(&client, org).await?);
+        }
+        Ok(client)
+    }
+
+    /// Returns a client targeting the organization `org_id` instead, the default organization if `None`
+    pub(crate) fn with_org(&self, org_id: Option<String>) -> ZitadelClient {
+        ZitadelClient {
+            org_id,
+            ..self.clone()
+        }
+    }
+
+    /// Sends the request and returns the parsed JSON response, an empty body is returned as an empty JSON object
+    pub(crate) async fn request(
+        &self,
+        method: Method,
+        endpoint: &str,
+        body: Option<&Value>,
+    ) -> Result<Value, ZitadelCLIError> {
+        let response = self.send(method, endpoint, body).await?;
+        let text = response.text().await?;
+        match text.is_empty() {
+            true => Ok(Value::Object(Default::default())),
+            false => serde_json::from_str(&text).map_err(ZitadelCLIError::InvalidResponse),
+        }
+    }
+
+    /// Sends the search `body` to `endpoint` once per page, as selected by `pagination`, and passes the resources
+    /// of each page to `on_page` as soon as it is received
+    /// - Stops at the first page shorter than requested, or once the total reported by the API is fetched
+    /// - Returns the total number of resources matching the search, as reported by the API, or the number of
+    ///   resources fetched if the API doesn't report it
+    pub(crate) async fn paginate(
+        &self,
+        endpoint: &str,
+        mut body: Value,
+        pagination: &Pagination,
+        mut on_page: impl FnMut(Vec<Value>) -> Result<(), ZitadelCLIError>,
+    ) -> Result<u64, ZitadelCLIError> {
+        let mut fetched = 0;
+        let mut total = None;
+        while let Some(limit) = pagination.next_limit(fetched) {
+            body["query"] = pagination.query(fetched, limit);
+            let response = self.request(Method::POST, endpoint, Some(&body)).await?;
+            total = total_result(&response);
+            let items = match response.get("result") {
+                Some(Value::Array(items)) => items.clone(),
+                _ => vec![],
+            };
+            let count = items.len() as u64;
+            on_page(items)?;
+            fetched += count;
+            if count < u64::from(limit) || total.is_some_and(|total| fetched >= total) {
+                break;
+            }
+        }
+        Ok(total.map_or(fetched, |total| total.max(fetched)))
+    }
+
+    /// Sends the request, retrying it with an exponential backoff while the API answers with a retryable status
+    /// - Returns `Err(ZitadelCLIError::Api(error))` with the decoded error if the API rejects the request
+    pub(crate) async fn send(
+        &self,
+        method: Method,
+        endpoint: &str,
+        body: Option<&Value>,
+    ) -> Result<Response, ZitadelCLIError> {
+        let mut attempt = 0;
+        loop {
+            let mut request = self
+                .http
+                .request(method.clone(), format!("{}{endpoint}", self.base_url))
+                .bearer_auth(&self.access_token);
+            if let Some(org_id) = &self.org_id {
+                request = request.header(ORG_ID_HEADER, org_id);
+            }
+            if let Some(body) = body {
+                request = request.json(body);
+            }
+            let response = request.send().await?;
+            let status = response.status();
+            if !is_retryable(&method, endpoint, status) || attempt == MAX_RETRIES {
+                return check_status(response).await;
+            }
+            let delay = retry_delay(attempt, response.headers().get(RETRY_AFTER));
+            warn!("{method} {endpoint} returned {status}, retrying in {delay:?}");
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+}
+
+/// Returns the `details.totalResult` of a search response, which the API encodes as a string
+/// - Returns `None` if the response doesn't report it, the total can't be trusted then
+fn total_result(response: &Value) -> Option<u64> {
+    match response.pointer("/details/totalResult")? {
+        Value::String(total) => total.parse().ok(),
+        total => total.as_u64(),
+    }
+}
+
+/// Returns whether the request may succeed if sent again later
+/// - A rate limited request wasn't processed, so it is always retried
+/// - A gateway error may hide a processed request, so only the idempotent ones are retried: `GET`, `PUT`,
+///   `DELETE` and the `POST` searches, `POST /v2/users` being the search of the users API v2. The endpoints
+///   generating a credential, like `PUT /management/v1/users/{id}/secret`, would rotate it twice and are excluded
+fn is_retryable(method: &Method, endpoint: &str, status: StatusCode) -> bool {
+    let generates_credential = endpoint
+        .rsplit('/')
+        .next()
+        .is_some_and(|segment| CREDENTIAL_SEGMENTS.contains(&segment));
+    let idempotent = (matches!(*method, Method::GET | Method::PUT | Method::DELETE)
+        && !generates_credential)
+        || (*method == Method::POST && (endpoint.ends_with("/_search") || endpoint == "/v2/users"));
+    match status {
+        StatusCode::TOO_MANY_REQUESTS => true,
+        StatusCode::BAD_GATEWAY | StatusCode::SERVICE_UNAVAILABLE | StatusCode::GATEWAY_TIMEOUT => {
+            idempotent
+        }
+        _ => false,
+    }
+}
+
+/// Returns the delay before the retry following `attempt`, the `Retry-After` header in seconds takes precedence
+/// over the exponential backoff
+fn retry_delay(attempt: u32, retry_after: Option<&HeaderValue>) -> Duration {
+    retry_after
+        .and_then(|value| value.to_str().ok()?.trim().parse::<u64>().ok())
+        .map(|seconds| Duration::from_secs(seconds).min(MAX_RETRY_AFTER))
+        .unwrap_or(INITIAL_BACKOFF * 2u32.pow(attempt))
+}
+
+/// Returns the response if its status code is a success, the error returned by the API otherwise
+/// - Returns `Err(ZitadelCLIError::Api(error))` with the decoded error of the API
+async fn check_status(response: Response) -> Result<Response, ZitadelCLIError> {
+    let status_code = response.status();
+    if status_code.is_success() {
+        return Ok(response);
+    }
+    let body = response.text().await?;
+    Err(ZitadelCLIError::Api(ApiError::from_response(
+        status_code,
+        &body,
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_retryable() {
+        let cases = [
+            (
+                Method::POST,
+                "/v2/users/human",
+                StatusCode::TOO_MANY_REQUESTS,
+                true,
+            ),
+            (
+                Method::POST,
+                "/v2/users/human",
+                StatusCode::GATEWAY_TIMEOUT,
+                false,
+            ),
+            (
+                Method::POST,
+                "/management/v1/users/_search",
+                StatusCode::BAD_GATEWAY,
+                true,
+            ),
+            (Method::POST, "/v2/users", StatusCode::BAD_GATEWAY, true),
+            (
+                Method::DELETE,
+                "/v2/users/1",
+                StatusCode::SERVICE_UNAVAILABLE,
+                true,
+            ),
+            (
+                Method::GET,
+                "/v2/users/1",
+                StatusCode::INTERNAL_SERVER_ERROR,
+                false,
+            ),
+            (
+                Method::PUT,
+                "/management/v1/users/1/secret",
+                StatusCode::BAD_GATEWAY,
+                false,
+            ),
+            (
+                Method::PUT,
+                "/management/v1/users/1/secret",
+                StatusCode::TOO_MANY_REQUESTS,
+                true,
+            ),
+            (
+                Method::PUT,
+                "/management/v1/users/1/keys",
+                StatusCode::SERVICE_UNAVAILABLE,
+                false,
+            ),
+            (
+                Method::PUT,
+                "/management/v1/users/1/pats",
+                StatusCode::GATEWAY_TIMEOUT,
+                false,
+            ),
+            (
+                Method::DELETE,
+                "/management/v1/users/1/pats/2",
+                StatusCode::BAD_GATEWAY,
+                true,
+            ),
+        ];
+        for (method, endpoint, status, retryable) in cases {
+            assert_eq!(
+                is_retryable(&method, endpoint, status),
+                retryable,
+                "{method} {endpoint} {status}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_retry_delay() {
+        assert_eq!(retry_delay(0, None), Duration::from_millis(500));
+        assert_eq!(retry_delay(3, None), Duration::from_secs(4));
+        let retry_after = HeaderValue::from_static("7");
+        assert_eq!(retry_delay(0, Some(&retry_after)), Duration::from_secs(7));
+        let retry_after = HeaderValue::from_static("3600");
+        assert_eq!(retry_delay(0, Some(&retry_after)), MAX_RETRY_AFTER);
+        let retry_after = HeaderValue::from_static("Wed, 21 Oct 2015 07:28:00 GMT");
+        assert_eq!(retry_delay(1, Some(&retry_after)), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_next_limit() {
+        let first_page = Pagination::default();
+        assert_eq!(first_page.next_limit(0), Some(DEFAULT_PAGE_SIZE));
+        assert_eq!(first_page.next_limit(100), None);
+
+        let limited = Pagination {
+            page_size: 1000,
+            limit: Some(2500),
+            ..Default::default()
+        };
+        assert_eq!(limited.next_limit(0), Some(1000));
+        assert_eq!(limited.next_limit(2000), Some(500));
+        assert_eq!(limited.next_limit(2500), None);
+
+        let all = Pagination {
+            limit: Some(10),
+            all: true,
+            ..Default::default()
+        };
+        assert_eq!(all.next_limit(40_000), Some(DEFAULT_PAGE_SIZE));
+        assert_eq!(
+            all.query(200, 100),
+            json!({ "offset": "200", "limit": 100 })
+        );
+    }
+
+    #[test]
+    fn test_total_result() {
+        assert_eq!(
+            total_result(&json!({ "details": { "totalResult": "40000" } })),
+            Some(40_000)
+        );
+        assert_eq!(
+            total_result(&json!({ "details": { "totalResult": 0 } })),
+            Some(0)
+        );
+        assert_eq!(total_result(&json!({ "result": [] })), None);
+    }
+}
@@ -1,77 +1,105 @@
-use reqwest::{
-    header::{HeaderValue, LOCATION},
-    Client, Response, StatusCode,
-};
+use reqwest::Method;
 use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{json, Value};
 use std::path::Path;
-use tracing::{error, info};
+use tracing::info;
 
-use crate::{error::ZitadelCLIError, payloads::load_from_file};
+use crate::{
+    client::ZitadelClient, config::Config, error::ZitadelCLIError, payloads::load_payloads,
+};
 
-use super::{load_access_token, Token};
+/// The outcome of [`add_payloads`]
+pub(crate) struct Added {
+    /// The number of payloads in the file
+    pub(crate) payloads: usize,
+    /// The responses of the created entities, in order
+    pub(crate) responses: Vec<Value>,
+    /// The error of the first rejected payload, the following ones aren't sent
+    pub(crate) result: Result<(), ZitadelCLIError>,
+}
 
-/// Generic function to add an entity to the Zitadel instance
-pub(crate) async fn handle_add_entity<T>(file_path: &Path, endpoint: &str)
+/// Loads the payloads `T` of `file_path` as the JSON bodies sent to the API
+/// A multi-document YAML file holds one payload per document
+pub(crate) fn load_payload_values<T>(
+    config: &Config,
+    file_path: &Path,
+) -> Result<Vec<Value>, ZitadelCLIError>
 where
-    T: serde::de::DeserializeOwned + Serialize,
+    T: DeserializeOwned + Serialize,
 {
-    match load_access_token() {
-        Ok(token) => match add_entity::<T>(token, endpoint, file_path).await {
-            Ok(location) => match location {
-                Some(location) => info!(
-                    "{} added successfully at {}",
-                    std::any::type_name::<T>(),
-                    location.to_str().unwrap()
-                ),
-                None => info!("{} added successfully", std::any::type_name::<T>()),
-            },
-            Err(error) => error!("Error adding {}: {}", std::any::type_name::<T>(), error),
-        },
-        Err(error) => error!("Error loading token: {}", error),
-    }
+    load_payloads::<T>(file_path, config.payload_format)?
+        .iter()
+        .map(|payload| Ok(serde_json::to_value(payload)?))
+        .collect()
 }
 
-pub(crate) async fn add_entity<T: DeserializeOwned + Serialize>(
-    token: Token,
+/// Sends the payloads to `endpoint` in order
+/// - Stops at the first rejected payload, the responses of the entities created before it are kept in [`Added`]
+pub(crate) async fn add_payloads(
+    client: &ZitadelClient,
+    payloads: Vec<Value>,
     endpoint: &str,
-    entity_file_path: &Path,
-) -> Result<Option<HeaderValue>, ZitadelCLIError> {
-    let issuer = std::env::var("ISSUER").expect("ISSUER env variable not found");
-    match load_from_file::<T>(entity_file_path) {
-        Ok(entity) => {
-            match add_entity_api_call(&token.access_token, &issuer, endpoint, entity).await {
-                Ok(response) => match response.status() {
-                    StatusCode::CREATED | StatusCode::OK => {
-                        Ok(response.headers().get(LOCATION).cloned())
-                    }
-                    StatusCode::UNAUTHORIZED => Err(ZitadelCLIError::ReqwestResponse(format!(
-                        "Invalid token or unauthorized access, please log in again: {}",
-                        response.text().await?,
-                    ))),
-                    status_code => Err(ZitadelCLIError::ReqwestResponse(format!(
-                        "Unhandled status code {status_code}: {}",
-                        response.text().await?,
-                    ))),
-                },
-                Err(error) => Err(error),
+) -> Added {
+    let count = payloads.len();
+    let mut responses = vec![];
+    for (index, body) in payloads.iter().enumerate() {
+        match client.request(Method::POST, endpoint, Some(body)).await {
+            Ok(response) => responses.push(response),
+            Err(error) => {
+                return Added {
+                    payloads: count,
+                    responses,
+                    result: Err(error),
+                }
             }
         }
-        Err(error) => Err(error),
+        match count {
+            1 => info!("POST {endpoint} succeeded"),
+            _ => info!("POST {endpoint} succeeded ({}/{count})", index + 1),
+        }
+    }
+    Added {
+        payloads: count,
+        responses,
+        result: Ok(()),
     }
 }
 
-async fn add_entity_api_call<T: Serialize>(
-    access_token: &str,
-    issuer: &str,
+/// Generic function to add an entity to the Zitadel instance, the created entity returned by the API is printed
+/// in the output format so its id can be used by scripts
+/// A multi-document YAML file adds one entity per document, in order, and the created entities are printed as a list
+/// - Stops at the first rejected document, the entities created before it are printed before the error is returned
+pub(crate) async fn handle_add_entity<T>(
+    config: &Config,
+    client: &ZitadelClient,
+    file_path: &Path,
     endpoint: &str,
-    entity: T,
-) -> Result<Response, ZitadelCLIError> {
-    let client = Client::new();
-    let request = client
-        .post(format!("{issuer}{endpoint}"))
-        .header("Authorization", format! {"Bearer {access_token}"})
-        .json(&entity)
-        .send()
-        .await?;
-    Ok(request)
+) -> Result<(), ZitadelCLIError>
+where
+    T: DeserializeOwned + Serialize,
+{
+    let payloads = load_payload_values::<T>(config, file_path)?;
+    handle_add_payloads(config, client, payloads, endpoint).await
+}
+
+/// Adds the entities of payloads already checked, see [`handle_add_entity`]
+pub(crate) async fn handle_add_payloads(
+    config: &Config,
+    client: &ZitadelClient,
+    payloads: Vec<Value>,
+    endpoint: &str,
+) -> Result<(), ZitadelCLIError> {
+    let Added {
+        payloads,
+        responses,
+        result,
+    } = add_payloads(client, payloads, endpoint).await;
+    match (payloads, responses.is_empty()) {
+        (_, true) => {}
+        (1, false) => config.output.print(&responses[0])?,
+        _ => config
+            .output
+            .print(&json!({ "result": Value::Array(responses) }))?,
+    }
+    result
 }
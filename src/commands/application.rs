@@ -0,0 +1,111 @@
+//! Creation of the applications and regeneration of their secret
+//! Zitadel only shows the client secret once, so it is either printed or written to a file
+
+use std::path::Path;
+
+use reqwest::Method;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{json, Map, Value};
+use tracing::info;
+
+use crate::{client::ZitadelClient, config::Config, error::ZitadelCLIError};
+
+use super::{
+    add::{add_payloads, load_payload_values, Added},
+    request::send_request,
+    secret::output_secret,
+};
+
+/// Adds an application to the project `project_id`, then outputs its id, client id and client secret
+/// A multi-document YAML file adds one application per document, and outputs the list of their credentials
+/// - `endpoint`: The endpoint of the application type, e.g. `/management/v1/projects/{project_id}/apps/oidc`
+/// - `output_file`: The file the credentials are written to, they are printed if missing
+/// - Stops at the first rejected document, the credentials of the applications added before it are output before
+///   the error is returned
+pub(crate) async fn handle_add_application<T>(
+    config: &Config,
+    client: &ZitadelClient,
+    file_path: &Path,
+    endpoint: &str,
+    output_file: Option<&Path>,
+) -> Result<(), ZitadelCLIError>
+where
+    T: DeserializeOwned + Serialize,
+{
+    let Added {
+        payloads,
+        responses,
+        result,
+    } = add_payloads(
+        client,
+        load_payload_values::<T>(config, file_path)?,
+        endpoint,
+    )
+    .await;
+    // The secrets are only returned once, so the ones of the added applications are output even after an error
+    let mut added: Vec<Value> = responses.iter().map(credentials).collect();
+    let credentials = match (payloads, added.len()) {
+        (_, 0) => return result,
+        (1, _) => added.remove(0),
+        _ => json!({ "result": added }),
+    };
+    output_secret(&config.output, output_file, &credentials)?;
+    match responses.len() {
+        1 => info!("Application added successfully"),
+        count => info!("{count} applications added successfully"),
+    }
+    result
+}
+
+/// Generates a new client secret for the application, the previous one stops working
+/// - `config_path`: Either `oidc_config` or `api_config`
+/// - `output_file`: The file the credentials are written to, they are printed if missing
+pub(crate) async fn handle_regenerate_secret(
+    config: &Config,
+    client: &ZitadelClient,
+    project_id: &str,
+    app_id: &str,
+    config_path: &str,
+    output_file: Option<&Path>,
+) -> Result<(), ZitadelCLIError> {
+    let endpoint = format!(
+        "/management/v1/projects/{project_id}/apps/{app_id}/{config_path}/_generate_client_secret"
+    );
+    let response = send_request(client, Method::POST, &endpoint, Some(json!({}))).await?;
+    output_secret(&config.output, output_file, &credentials(&response))?;
+    info!("Client secret of the application {app_id} regenerated successfully");
+    Ok(())
+}
+
+/// Keeps the identifiers and secrets of the response, which are the only fields worth keeping
+fn credentials(response: &Value) -> Value {
+    let credentials: Map<String, Value> = ["appId", "clientId", "clientSecret"]
+        .into_iter()
+        .filter_map(|key| {
+            response
+                .get(key)
+                .map(|value| (key.to_string(), value.clone()))
+        })
+        .collect();
+    Value::Object(credentials)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_credentials() {
+        let response = json!({
+            "appId": "1",
+            "details": { "sequence": "2" },
+            "clientId": "client_id",
+            "clientSecret": "client_secret",
+            "noneCompliant": false
+        });
+        assert_eq!(
+            credentials(&response),
+            json!({ "appId": "1", "clientId": "client_id", "clientSecret": "client_secret" })
+        );
+    }
+}
@@ -0,0 +1,183 @@
+//! The live configuration of an organization, read by `apply` to compute its plan and by `export`
+//! The resources are reshaped like the specifications of the manifests so they can be compared
+
+use reqwest::Method;
+use serde_json::{json, Value};
+
+use crate::{
+    client::{Pagination, ZitadelClient},
+    error::ZitadelCLIError,
+    payloads::{manifest::Kind, policy::PolicyType},
+};
+
+/// A resource of the live configuration
+pub(crate) struct LiveResource {
+    pub(crate) id: String,
+    /// The value matched with the manifests
+    pub(crate) key: String,
+    /// The name shown in the plan
+    pub(crate) name: String,
+    /// The resource with the fields of its configuration at the top level, like the manifests
+    pub(crate) view: Value,
+}
+
+/// Lists the live resources of a kind, in the organization targeted by `client`
+/// - `project_id`: The project of the roles, applications, grants and project members
+pub(crate) async fn list(
+    client: &ZitadelClient,
+    kind: Kind,
+    project_id: Option<&str>,
+) -> Result<Vec<LiveResource>, ZitadelCLIError> {
+    let project_id = project_id.unwrap_or_default();
+    let resources = match kind {
+        Kind::Organization => vec![],
+        Kind::Policy => return policies(client).await,
+        Kind::Idp => search(client, "/management/v1/idps/templates/_search")
+            .await?
+            .iter()
+            // The identity providers of the instance are also listed, but can't be managed from an organization
+            .filter(|idp| idp["owner"] == "IDP_OWNER_TYPE_ORG")
+            .filter_map(idp)
+            .collect(),
+        Kind::Action => search(client, "/management/v1/actions/_search")
+            .await?
+            .iter()
+            .filter_map(|action| resource(action, "id", "name"))
+            .collect(),
+        Kind::Project => search(client, "/management/v1/projects/_search")
+            .await?
+            .iter()
+            .filter_map(|project| resource(project, "id", "name"))
+            .collect(),
+        Kind::ProjectRole => {
+            let endpoint = format!("/management/v1/projects/{project_id}/roles/_search");
+            search(client, &endpoint)
+                .await?
+                .iter()
+                .filter_map(|role| resource(role, "key", "key"))
+                .collect()
+        }
+        Kind::App => {
+            let endpoint = format!("/management/v1/projects/{project_id}/apps/_search");
+            search(client, &endpoint)
+                .await?
+                .iter()
+                .filter_map(app)
+                .collect()
+        }
+        Kind::ProjectGrant => {
+            let endpoint = format!("/management/v1/projects/{project_id}/grants/_search");
+            search(client, &endpoint)
+                .await?
+                .iter()
+                .filter_map(grant)
+                .collect()
+        }
+        Kind::Member => {
+            let endpoint = match project_id.is_empty() {
+                true => "/management/v1/orgs/me/members/_search".to_string(),
+                false => format!("/management/v1/projects/{project_id}/members/_search"),
+            };
+            search(client, &endpoint)
+                .await?
+                .iter()
+                .filter_map(|member| resource(member, "userId", "userId"))
+                .collect()
+        }
+    };
+    Ok(resources)
+}
+
+/// Lists every resource of a search endpoint, over all the pages
+async fn search(client: &ZitadelClient, endpoint: &str) -> Result<Vec<Value>, ZitadelCLIError> {
+    let pagination = Pagination {
+        page_size: 1000,
+        all: true,
+        ..Default::default()
+    };
+    let mut resources = vec![];
+    client
+        .paginate(endpoint, json!({}), &pagination, |page| {
+            resources.extend(page);
+            Ok(())
+        })
+        .await?;
+    Ok(resources)
+}
+
+/// Lists the policies customized by the organization, identified by their path
+async fn policies(client: &ZitadelClient) -> Result<Vec<LiveResource>, ZitadelCLIError> {
+    let mut policies = vec![];
+    for policy_type in PolicyType::ALL {
+        let endpoint = format!("/management/v1/policies/{}", policy_type.path());
+        let response = client.request(Method::GET, &endpoint, None).await?;
+        let policy = &response["policy"];
+        // The organizations without their own policy get the one of the instance
+        if policy["isDefault"].as_bool().unwrap_or(true) {
+            continue;
+        }
+        policies.push(LiveResource {
+            id: policy_type.path().to_string(),
+            key: policy_type.as_str().to_string(),
+            name: policy_type.as_str().to_string(),
+            view: policy.clone(),
+        });
+    }
+    Ok(policies)
+}
+
+/// Returns a live resource identified by `id_field` and matched by `name_field`
+fn resource(item: &Value, id_field: &str, name_field: &str) -> Option<LiveResource> {
+    let name = item.get(name_field)?.as_str()?.to_string();
+    Some(LiveResource {
+        id: item.get(id_field)?.as_str()?.to_string(),
+        key: name.clone(),
+        name,
+        view: item.clone(),
+    })
+}
+
+/// Returns a live identity provider, the fields of its template are moved to the top level
+fn idp(item: &Value) -> Option<LiveResource> {
+    let mut resource = resource(item, "id", "name")?;
+    hoist(&mut resource.view, "config");
+    let templates = item["config"]
+        .as_object()
+        .map(|config| config.keys().cloned().collect::<Vec<_>>())
+        .unwrap_or_default();
+    for template in templates.iter().filter(|key| *key != "providerOptions") {
+        hoist(&mut resource.view, template);
+    }
+    Some(resource)
+}
+
+/// Returns a live application, the fields of its configuration are moved to the top level
+fn app(item: &Value) -> Option<LiveResource> {
+    let mut resource = resource(item, "id", "name")?;
+    for config in ["oidcConfig", "apiConfig", "samlConfig"] {
+        hoist(&mut resource.view, config);
+    }
+    Some(resource)
+}
+
+/// Returns a live project grant, matched by the id of the granted organization
+fn grant(item: &Value) -> Option<LiveResource> {
+    Some(LiveResource {
+        id: item.get("grantId")?.as_str()?.to_string(),
+        key: item.get("grantedOrgId")?.as_str()?.to_string(),
+        name: item.get("grantedOrgName")?.as_str()?.to_string(),
+        view: json!({ "roleKeys": item.get("grantedRoleKeys").cloned().unwrap_or(json!([])) }),
+    })
+}
+
+/// Copies the fields of the object `field` to the top level of `view`, without overriding the existing ones
+pub(crate) fn hoist(view: &mut Value, field: &str) {
+    let Some(Value::Object(nested)) = view.get(field).cloned() else {
+        return;
+    };
+    if let Some(view) = view.as_object_mut() {
+        for (key, value) in nested {
+            view.entry(key).or_insert(value);
+        }
+    }
+}
@@ -0,0 +1,118 @@
+//! The `apply` command, which reconciles the live configuration with the manifests of [`crate::payloads::manifest`]
+//! The plan is printed to stderr as a diff before being applied: `+` for the creations, `~` for the updates with the
+//! changed fields, and `-` for the deletions of `--prune`
+
+pub(crate) mod live;
+mod plan;
+
+use std::path::Path;
+
+use serde_json::{json, Value};
+use tracing::info;
+
+use crate::{
+    client::ZitadelClient,
+    config::Config,
+    error::ZitadelCLIError,
+    payloads::manifest::{load_manifests, Manifest},
+};
+
+use super::{secret::output_secret, API_KEYS};
+use plan::{Change, Plan};
+
+/// Applies the manifests of `file_path`, a YAML file or a directory of YAML files
+/// - `prune`: Deletes the resources of the described organizations and projects that have no manifest
+/// - `dry_run`: Only prints the plan
+pub(crate) async fn handle_apply(
+    config: &Config,
+    file_path: &Path,
+    prune: bool,
+    dry_run: bool,
+) -> Result<(), ZitadelCLIError> {
+    config.validate(API_KEYS)?;
+    let manifests = load_manifests(file_path)?;
+    let client = ZitadelClient::new(config).await?;
+    let mut plan = Plan::new(&client, &manifests, prune).await?;
+    eprint!("{plan}");
+    if dry_run {
+        return Ok(());
+    }
+    let mut applied = Applied::default();
+    let result = apply(&client, &mut plan, &mut applied).await;
+    // The client secrets of the created applications are only returned once, so they are printed even if a
+    // later change fails, along with the changes applied and the ones left for the next `apply`
+    let document = match &result {
+        Ok(()) if applied.credentials.is_empty() => return Ok(()),
+        Ok(()) => json!({ "result": applied.credentials }),
+        Err(_) => json!({
+            "result": applied.credentials,
+            "applied": applied.labels,
+            "remaining": plan.changes.iter().map(Change::label).collect::<Vec<_>>(),
+        }),
+    };
+    output_secret(&config.output, None, &document)?;
+    result
+}
+
+/// The changes of the plan applied so far
+#[derive(Default)]
+struct Applied {
+    labels: Vec<String>,
+    /// The credentials of the created applications
+    credentials: Vec<Value>,
+}
+
+/// Sends the requests of the plan in order, recording the applied changes in `applied`
+/// - Stops at the first rejected request, the changes left for the next `apply` stay in `plan.changes`
+async fn apply(
+    client: &ZitadelClient,
+    plan: &mut Plan<'_>,
+    applied: &mut Applied,
+) -> Result<(), ZitadelCLIError> {
+    let changes = std::mem::take(&mut plan.changes);
+    let mut changes = changes.into_iter();
+    while let Some(change) = changes.next() {
+        if let Err(error) = apply_change(client, plan, &change, applied).await {
+            plan.changes = std::iter::once(change).chain(changes).collect();
+            return Err(error);
+        }
+    }
+    Ok(())
+}
+
+/// Sends the request of a change
+async fn apply_change(
+    client: &ZitadelClient,
+    plan: &mut Plan<'_>,
+    change: &Change<'_>,
+    applied: &mut Applied,
+) -> Result<(), ZitadelCLIError> {
+    let label = change.label();
+    let request = match change {
+        Change::Create(manifest) => plan.create_request(manifest)?,
+        Change::Update { request, .. } | Change::Delete { request, .. } => request.clone(),
+    };
+    let client = plan
+        .client_for(client, request.org.as_deref())
+        .ok_or_else(|| {
+            ZitadelCLIError::Manifest(format!("{label}: its organization isn't created"))
+        })?;
+    let response = client
+        .request(
+            request.method.clone(),
+            &request.endpoint,
+            request.body.as_ref(),
+        )
+        .await?;
+    info!("{} {label} succeeded", request.method);
+    if let Change::Create(manifest) = change {
+        plan.record_created(manifest, &response);
+        if let Manifest::App { .. } = manifest {
+            applied
+                .credentials
+                .push(json!({ "app": label, "credentials": response }));
+        }
+    }
+    applied.labels.push(label);
+    Ok(())
+}
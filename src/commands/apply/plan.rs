@@ -0,0 +1,722 @@
+//! The plan of `apply`: the changes bringing the live configuration to the one described by the manifests
+//! The live resources are matched with the manifests by name, the key of the roles and the granted organization
+//! of the project grants
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt::Display,
+};
+
+use reqwest::Method;
+use serde_json::{json, Value};
+
+use crate::{
+    client::ZitadelClient,
+    commands::org::find_org_id,
+    error::ZitadelCLIError,
+    payloads::manifest::{label, redacted_fields, Kind, Manifest, SECRET_FIELDS},
+};
+
+use super::live::{self, LiveResource};
+
+/// A field whose live value differs from the manifest
+#[derive(Debug, PartialEq)]
+pub(crate) struct FieldChange {
+    /// The dotted path of the field, e.g. `providerOptions.isAutoCreation`
+    path: String,
+    live: Value,
+    desired: Value,
+}
+
+/// A request of the plan, sent to the organization named `org`, the one targeted by `--org` if `None`
+#[derive(Clone)]
+pub(crate) struct Request {
+    pub(crate) org: Option<String>,
+    pub(crate) method: Method,
+    pub(crate) endpoint: String,
+    pub(crate) body: Option<Value>,
+}
+
+pub(crate) enum Change<'a> {
+    /// The resource doesn't exist, its request is built once the resources it depends on are created
+    Create(&'a Manifest),
+    Update {
+        manifest: &'a Manifest,
+        fields: Vec<FieldChange>,
+        request: Request,
+    },
+    /// The resource isn't described by any manifest, only planned with `--prune`
+    Delete {
+        kind: Kind,
+        label: String,
+        request: Request,
+    },
+}
+
+impl Change<'_> {
+    pub(crate) fn label(&self) -> String {
+        match self {
+            Change::Create(manifest) | Change::Update { manifest, .. } => manifest.label(),
+            Change::Delete { label, .. } => label.clone(),
+        }
+    }
+
+    /// Returns the position of the change in the plan: the resources are created and updated in dependency order,
+    /// then deleted in the reverse order so nothing is removed if the creations fail
+    fn order(&self) -> (bool, i8) {
+        match self {
+            Change::Create(manifest) | Change::Update { manifest, .. } => {
+                (false, manifest.kind() as i8)
+            }
+            Change::Delete { kind, .. } => (true, -(*kind as i8)),
+        }
+    }
+}
+
+/// The organization and project the resources being reconciled belong to
+#[derive(Clone, Copy)]
+struct Scope<'s> {
+    org: Option<&'s str>,
+    /// The name and id of the project
+    project: Option<(&'s str, &'s str)>,
+}
+
+#[derive(Default)]
+pub(crate) struct Plan<'a> {
+    pub(crate) changes: Vec<Change<'a>>,
+    /// The number of resources already matching their manifest
+    pub(crate) unchanged: usize,
+    /// The ids of the organizations by name, completed as they are created
+    org_ids: HashMap<String, String>,
+    /// The ids of the projects by organization and name, completed as they are created
+    project_ids: HashMap<(Option<String>, String), String>,
+    /// The id of the authenticated user, whose memberships are never deleted by `--prune`
+    user_id: Option<String>,
+}
+
+impl<'a> Plan<'a> {
+    /// Compares the manifests with the live configuration
+    /// - `prune`: Whether the resources without a manifest are deleted. Only the resources of the organizations
+    ///   and projects described by the manifests are considered, and only the kinds with at least one manifest in
+    ///   the organization or project. The organizations and the memberships of the authenticated user are never
+    ///   deleted
+    /// - Returns `Err(ZitadelCLIError::Manifest(error))` if a manifest is duplicated, invalid or references a
+    ///   resource that neither exists nor is described
+    pub(crate) async fn new(
+        client: &ZitadelClient,
+        manifests: &'a [Manifest],
+        prune: bool,
+    ) -> Result<Plan<'a>, ZitadelCLIError> {
+        check_duplicates(manifests)?;
+        let mut plan = Plan::default();
+        if prune {
+            let user = client
+                .request(Method::GET, "/auth/v1/users/me", None)
+                .await?;
+            plan.user_id = user["user"]["id"].as_str().map(str::to_string);
+        }
+        plan.plan_orgs(client, manifests).await?;
+        let orgs: BTreeSet<Option<&str>> = manifests
+            .iter()
+            .filter(|manifest| manifest.kind() != Kind::Organization)
+            .map(Manifest::org)
+            .collect();
+        for org in orgs {
+            let in_org = manifests
+                .iter()
+                .filter(|manifest| manifest.kind() != Kind::Organization && manifest.org() == org)
+                .collect::<Vec<_>>();
+            match plan.client_for(client, org) {
+                Some(client) => plan.plan_org(&client, org, &in_org, prune).await?,
+                // The organization is created by the plan, so are all its resources
+                None => plan.changes.extend(in_org.into_iter().map(Change::Create)),
+            }
+        }
+        plan.changes.sort_by_key(Change::order);
+        Ok(plan)
+    }
+
+    /// Returns the client sending the requests to the organization named `org`, `None` if it isn't created yet
+    pub(crate) fn client_for(
+        &self,
+        client: &ZitadelClient,
+        org: Option<&str>,
+    ) -> Option<ZitadelClient> {
+        match org {
+            None => Some(client.clone()),
+            Some(org) => self
+                .org_ids
+                .get(org)
+                .map(|org_id| client.with_org(Some(org_id.clone()))),
+        }
+    }
+
+    /// Resolves the organizations described or referenced by the manifests, the missing ones are created
+    async fn plan_orgs(
+        &mut self,
+        client: &ZitadelClient,
+        manifests: &'a [Manifest],
+    ) -> Result<(), ZitadelCLIError> {
+        let mut names = BTreeSet::new();
+        for manifest in manifests {
+            match manifest {
+                Manifest::Organization { spec } => names.insert(spec.name.as_str()),
+                Manifest::ProjectGrant { granted_org, .. } => names.insert(granted_org.as_str()),
+                manifest => manifest.org().is_some_and(|org| names.insert(org)),
+            };
+        }
+        for name in names {
+            let declared = manifests
+                .iter()
+                .find(|manifest| manifest.kind() == Kind::Organization && manifest.name() == name);
+            match (find_org_id(client, name).await?, declared) {
+                (Some(org_id), declared) => {
+                    self.org_ids.insert(name.to_string(), org_id);
+                    self.unchanged += usize::from(declared.is_some());
+                }
+                (None, Some(manifest)) => self.changes.push(Change::Create(manifest)),
+                (None, None) => {
+                    return Err(ZitadelCLIError::Manifest(format!(
+                        "The organization {name} doesn't exist and isn't described by any manifest"
+                    )))
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Plans the changes of the resources of an existing organization
+    async fn plan_org(
+        &mut self,
+        client: &ZitadelClient,
+        org: Option<&str>,
+        manifests: &[&'a Manifest],
+        prune: bool,
+    ) -> Result<(), ZitadelCLIError> {
+        let of_kind = |kind: Kind, project: Option<&str>| {
+            manifests
+                .iter()
+                .copied()
+                .filter(move |manifest| manifest.kind() == kind && manifest.project() == project)
+                .map(|manifest| (manifest, manifest.name()))
+                .collect::<Vec<_>>()
+        };
+        let scope = Scope { org, project: None };
+        for kind in [Kind::Idp, Kind::Policy, Kind::Action, Kind::Member] {
+            let live = live::list(client, kind, None).await?;
+            self.reconcile(scope, kind, of_kind(kind, None), live, prune)?;
+        }
+
+        let projects = live::list(client, Kind::Project, None).await?;
+        for project in &projects {
+            let key = (org.map(str::to_string), project.name.clone());
+            self.project_ids.insert(key, project.id.clone());
+        }
+        let declared_projects = of_kind(Kind::Project, None);
+        self.reconcile(
+            scope,
+            Kind::Project,
+            declared_projects.clone(),
+            projects,
+            prune,
+        )?;
+
+        let mut project_names: BTreeSet<String> = manifests
+            .iter()
+            .filter_map(|manifest| manifest.project())
+            .map(str::to_string)
+            .collect();
+        if prune {
+            project_names.extend(declared_projects.iter().map(|(_, name)| name.clone()));
+        }
+        for project in &project_names {
+            let declared = declared_projects.iter().any(|(_, name)| name == project);
+            let children = [
+                Kind::ProjectRole,
+                Kind::App,
+                Kind::ProjectGrant,
+                Kind::Member,
+            ]
+            .map(|kind| (kind, of_kind(kind, Some(project))));
+            let project_id = self
+                .project_ids
+                .get(&(org.map(str::to_string), project.clone()));
+            let Some(project_id) = project_id.cloned() else {
+                if !declared {
+                    let referencing = manifests
+                        .iter()
+                        .find(|manifest| manifest.project() == Some(project.as_str()))
+                        .map(|manifest| manifest.label())
+                        .unwrap_or_default();
+                    return Err(ZitadelCLIError::Manifest(format!(
+                        "{referencing}: the project {project} doesn't exist and isn't described by any manifest"
+                    )));
+                }
+                for (_, children) in children {
+                    self.changes.extend(
+                        children
+                            .into_iter()
+                            .map(|(manifest, _)| Change::Create(manifest)),
+                    );
+                }
+                continue;
+            };
+            let scope = Scope {
+                org,
+                project: Some((project, &project_id)),
+            };
+            for (kind, children) in children {
+                let live = live::list(client, kind, Some(&project_id)).await?;
+                // The grants are matched by the id of the granted organization, which may still be missing
+                let children = match kind {
+                    Kind::ProjectGrant => children
+                        .into_iter()
+                        .map(|(manifest, name)| {
+                            let org_id = self.org_ids.get(&name).cloned();
+                            (manifest, org_id.unwrap_or(name))
+                        })
+                        .collect(),
+                    _ => children,
+                };
+                self.reconcile(scope, kind, children, live, prune && declared)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Plans the changes of the resources of a kind, matching the manifests with the live resources by key
+    fn reconcile(
+        &mut self,
+        scope: Scope,
+        kind: Kind,
+        desired: Vec<(&'a Manifest, String)>,
+        live: Vec<LiveResource>,
+        prune: bool,
+    ) -> Result<(), ZitadelCLIError> {
+        for (manifest, key) in &desired {
+            let Some(resource) = live.iter().find(|resource| &resource.key == key) else {
+                self.changes.push(Change::Create(manifest));
+                continue;
+            };
+            if let Manifest::App { app_type, .. } = manifest {
+                if resource.view.get(app_type.config_field()).is_none() {
+                    return Err(ZitadelCLIError::Manifest(format!(
+                        "{}: the type of an application can't be changed, delete it first",
+                        manifest.label()
+                    )));
+                }
+            }
+            let spec = manifest.spec()?;
+            let fields = diff(&spec, &resource.view, "");
+            if fields.is_empty() {
+                self.unchanged += 1;
+                continue;
+            }
+            let (endpoint, body) = update_request(manifest, spec, &resource.id, scope)?;
+            self.changes.push(Change::Update {
+                manifest,
+                fields,
+                request: Request {
+                    org: scope.org.map(str::to_string),
+                    method: Method::PUT,
+                    endpoint,
+                    body: Some(body),
+                },
+            });
+        }
+        // Without any manifest of the kind, the resources are left alone rather than all deleted
+        if !prune || desired.is_empty() {
+            return Ok(());
+        }
+        for resource in live.iter().filter(|resource| {
+            desired.iter().all(|(_, key)| key != &resource.key)
+                && !(kind == Kind::Member && self.user_id.as_ref() == Some(&resource.key))
+        }) {
+            let project = scope.project.map(|(name, _)| name);
+            self.changes.push(Change::Delete {
+                kind,
+                label: label(kind, scope.org, project, &resource.name),
+                request: Request {
+                    org: scope.org.map(str::to_string),
+                    method: Method::DELETE,
+                    endpoint: delete_endpoint(kind, &resource.id, scope)?,
+                    body: None,
+                },
+            });
+        }
+        Ok(())
+    }
+
+    /// Builds the request creating the resource of `manifest`, once the resources it depends on are created
+    /// - Returns `Err(ZitadelCLIError::Manifest(error))` if a secret of the manifest was redacted by `export`
+    pub(crate) fn create_request(&self, manifest: &Manifest) -> Result<Request, ZitadelCLIError> {
+        let spec = manifest.spec()?;
+        if let Some(field) = redacted_fields(&spec).first() {
+            return Err(ZitadelCLIError::Manifest(format!(
+                "{}: `{field}` is redacted, set the secret before creating it",
+                manifest.label()
+            )));
+        }
+        let (endpoint, body) = match manifest {
+            Manifest::Organization { .. } => ("/v2/organizations".to_string(), spec),
+            Manifest::Idp { idp_type, .. } => {
+                (format!("/management/v1/idps/{}", idp_type.path()), spec)
+            }
+            Manifest::Policy { policy_type, .. } => (
+                format!("/management/v1/policies/{}", policy_type.path()),
+                spec,
+            ),
+            Manifest::Action { .. } => ("/management/v1/actions".to_string(), spec),
+            Manifest::Member { project: None, .. } => {
+                ("/management/v1/orgs/me/members".to_string(), spec)
+            }
+            Manifest::Member { .. } => {
+                let project_id = self.project_id(manifest)?;
+                let endpoint = format!("/management/v1/projects/{project_id}/members");
+                (endpoint, spec)
+            }
+            Manifest::Project { .. } => ("/management/v1/projects".to_string(), spec),
+            Manifest::ProjectRole { .. } => {
+                let project_id = self.project_id(manifest)?;
+                let endpoint = format!("/management/v1/projects/{project_id}/roles/_bulk");
+                (endpoint, json!({ "roles": [spec] }))
+            }
+            Manifest::App { app_type, .. } => {
+                let project_id = self.project_id(manifest)?;
+                let endpoint = format!(
+                    "/management/v1/projects/{project_id}/apps/{}",
+                    app_type.path()
+                );
+                (endpoint, spec)
+            }
+            Manifest::ProjectGrant {
+                granted_org,
+                role_keys,
+                ..
+            } => {
+                let project_id = self.project_id(manifest)?;
+                let endpoint = format!("/management/v1/projects/{project_id}/grants");
+                let granted_org_id = self.org_ids.get(granted_org).ok_or_else(|| {
+                    ZitadelCLIError::Manifest(format!(
+                        "{}: no organization named {granted_org}",
+                        manifest.label()
+                    ))
+                })?;
+                (
+                    endpoint,
+                    json!({ "grantedOrgId": granted_org_id, "roleKeys": role_keys }),
+                )
+            }
+        };
+        Ok(Request {
+            org: manifest.org().map(str::to_string),
+            method: Method::POST,
+            endpoint,
+            body: Some(body),
+        })
+    }
+
+    /// Records the id of a created organization or project, its resources can then be created
+    pub(crate) fn record_created(&mut self, manifest: &Manifest, response: &Value) {
+        let id = |field: &str| response[field].as_str().map(str::to_string);
+        match manifest {
+            Manifest::Organization { spec } => {
+                if let Some(org_id) = id("organizationId") {
+                    self.org_ids.insert(spec.name.clone(), org_id);
+                }
+            }
+            Manifest::Project { spec, .. } => {
+                if let Some(project_id) = id("id") {
+                    let key = (manifest.org().map(str::to_string), spec.name.clone());
+                    self.project_ids.insert(key, project_id);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns the id of the project of `manifest`
+    fn project_id(&self, manifest: &Manifest) -> Result<&String, ZitadelCLIError> {
+        let project = manifest.project().unwrap_or_default().to_string();
+        self.project_ids
+            .get(&(manifest.org().map(str::to_string), project))
+            .ok_or_else(|| {
+                ZitadelCLIError::Manifest(format!(
+                    "{}: the project isn't created",
+                    manifest.label()
+                ))
+            })
+    }
+}
+
+impl Display for Plan<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (mut created, mut updated, mut deleted) = (0, 0, 0);
+        for change in &self.changes {
+            match change {
+                Change::Create(_) => {
+                    created += 1;
+                    writeln!(f, "+ {}", change.label())?;
+                }
+                Change::Update { fields, .. } => {
+                    updated += 1;
+                    writeln!(f, "~ {}", change.label())?;
+                    for field in fields {
+                        writeln!(f, "    {}: {} -> {}", field.path, field.live, field.desired)?;
+                    }
+                }
+                Change::Delete { .. } => {
+                    deleted += 1;
+                    writeln!(f, "- {}", change.label())?;
+                }
+            }
+        }
+        writeln!(
+            f,
+            "Plan: {created} to create, {updated} to update, {deleted} to delete, {} unchanged",
+            self.unchanged
+        )
+    }
+}
+
+/// Checks that no resource is described twice
+fn check_duplicates(manifests: &[Manifest]) -> Result<(), ZitadelCLIError> {
+    let mut labels = BTreeSet::new();
+    for manifest in manifests {
+        let label = manifest.label();
+        if !labels.insert(label.clone()) {
+            return Err(ZitadelCLIError::Manifest(format!(
+                "{label} is described several times"
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Returns the endpoint and body updating a live resource with the specification of its manifest
+/// The secrets redacted by `export` are removed, so the current ones are kept
+/// - Returns `Err(ZitadelCLIError::Manifest(error))` for an organization, which is never updated
+fn update_request(
+    manifest: &Manifest,
+    spec: Value,
+    id: &str,
+    scope: Scope,
+) -> Result<(String, Value), ZitadelCLIError> {
+    let project_id = scope.project.map(|(_, id)| id).unwrap_or_default();
+    let without = |mut spec: Value, field: &str| {
+        if let Some(spec) = spec.as_object_mut() {
+            spec.remove(field);
+        }
+        spec
+    };
+    let redacted = redacted_fields(&spec)
+        .into_iter()
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+    let spec = redacted
+        .iter()
+        .fold(spec, |spec, field| without(spec, field));
+    Ok(match manifest {
+        Manifest::Policy { policy_type, .. } => (
+            format!("/management/v1/policies/{}", policy_type.path()),
+            spec,
+        ),
+        Manifest::Action { .. } => (format!("/management/v1/actions/{id}"), spec),
+        Manifest::Member { project: None, .. } => (
+            format!("/management/v1/orgs/me/members/{id}"),
+            without(spec, "userId"),
+        ),
+        Manifest::Member { .. } => (
+            format!("/management/v1/projects/{project_id}/members/{id}"),
+            without(spec, "userId"),
+        ),
+        Manifest::Idp { idp_type, .. } => (
+            format!("/management/v1/idps/{}/{id}", idp_type.path()),
+            spec,
+        ),
+        Manifest::ProjectRole { .. } => (
+            format!("/management/v1/projects/{project_id}/roles/{id}"),
+            without(spec, "key"),
+        ),
+        Manifest::App { app_type, .. } => (
+            format!(
+                "/management/v1/projects/{project_id}/apps/{id}/{}_config",
+                app_type.path()
+            ),
+            without(spec, "name"),
+        ),
+        Manifest::ProjectGrant { .. } => (
+            format!("/management/v1/projects/{project_id}/grants/{id}"),
+            spec,
+        ),
+        Manifest::Project { .. } => (format!("/management/v1/projects/{id}"), spec),
+        Manifest::Organization { .. } => {
+            return Err(ZitadelCLIError::Manifest(format!(
+                "{}: an organization can't be updated",
+                manifest.label()
+            )))
+        }
+    })
+}
+
+/// Returns the endpoint deleting a live resource
+/// - Returns `Err(ZitadelCLIError::Manifest(error))` for an organization, which is never deleted
+fn delete_endpoint(kind: Kind, id: &str, scope: Scope) -> Result<String, ZitadelCLIError> {
+    let project_id = scope.project.map(|(_, id)| id).unwrap_or_default();
+    Ok(match kind {
+        Kind::Idp => format!("/management/v1/idps/{id}"),
+        // The id of a policy is its path, deleting it restores the policy of the instance
+        Kind::Policy => format!("/management/v1/policies/{id}"),
+        Kind::Action => format!("/management/v1/actions/{id}"),
+        Kind::Member => match scope.project {
+            Some((_, project_id)) => format!("/management/v1/projects/{project_id}/members/{id}"),
+            None => format!("/management/v1/orgs/me/members/{id}"),
+        },
+        Kind::ProjectRole => format!("/management/v1/projects/{project_id}/roles/{id}"),
+        Kind::App => format!("/management/v1/projects/{project_id}/apps/{id}"),
+        Kind::ProjectGrant => format!("/management/v1/projects/{project_id}/grants/{id}"),
+        Kind::Project => format!("/management/v1/projects/{id}"),
+        Kind::Organization => {
+            return Err(ZitadelCLIError::Manifest(format!(
+                "the organization {id} can't be deleted"
+            )))
+        }
+    })
+}
+
+/// Returns the fields of `desired` whose live value differs, recursively
+/// The `null` fields of the manifests are left unchanged, and the fields missing from the live resource can't be
+/// compared, e.g. the secrets
+fn diff(desired: &Value, live: &Value, path: &str) -> Vec<FieldChange> {
+    match (desired, live) {
+        (Value::Object(desired), Value::Object(live)) => desired
+            .iter()
+            .filter(|(key, value)| !value.is_null() && !SECRET_FIELDS.contains(&key.as_str()))
+            .flat_map(|(key, value)| match live.get(key) {
+                Some(live) => diff(value, live, &field_path(path, key)),
+                None => vec![],
+            })
+            .collect(),
+        (desired, live) if same(desired, live) => vec![],
+        (desired, live) => vec![FieldChange {
+            path: path.to_string(),
+            live: live.clone(),
+            desired: desired.clone(),
+        }],
+    }
+}
+
+fn field_path(path: &str, key: &str) -> String {
+    match path.is_empty() {
+        true => key.to_string(),
+        false => format!("{path}.{key}"),
+    }
+}
+
+/// Returns whether two values are equal, the order of the lists of scalars (e.g. redirect uris) doesn't matter
+fn same(desired: &Value, live: &Value) -> bool {
+    let sorted = |values: &Vec<Value>| {
+        let mut values = values.iter().map(Value::to_string).collect::<Vec<_>>();
+        values.sort();
+        values
+    };
+    match (desired, live) {
+        (Value::Array(desired), Value::Array(live))
+            if desired.iter().chain(live).all(|value| !value.is_object()) =>
+        {
+            sorted(desired) == sorted(live)
+        }
+        (desired, live) => desired == live,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff() {
+        let desired = json!({
+            "name": "Google",
+            "clientSecret": "secret",
+            "scopes": ["openid", "email"],
+            "providerOptions": { "isAutoCreation": true, "autoLinking": null }
+        });
+        let mut live = json!({
+            "id": "123",
+            "name": "Google",
+            "config": {
+                "providerOptions": { "isAutoCreation": false, "autoLinking": "AUTO_LINKING_OPTION_EMAIL" },
+                "google": { "clientId": "client-id", "scopes": ["email", "openid"] }
+            }
+        });
+        live::hoist(&mut live, "config");
+        live::hoist(&mut live, "google");
+        assert_eq!(
+            diff(&desired, &live, ""),
+            [FieldChange {
+                path: "providerOptions.isAutoCreation".to_string(),
+                live: json!(false),
+                desired: json!(true),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_delete_endpoint() {
+        let scope = Scope {
+            org: None,
+            project: Some(("shop", "42")),
+        };
+        assert_eq!(
+            delete_endpoint(Kind::App, "7", scope).unwrap(),
+            "/management/v1/projects/42/apps/7"
+        );
+        assert!(matches!(
+            delete_endpoint(Kind::Organization, "1", scope),
+            Err(ZitadelCLIError::Manifest(_))
+        ));
+    }
+
+    #[test]
+    fn test_change_order() {
+        let manifests: Vec<Manifest> = serde_yaml::from_str(
+            "[{ kind: ProjectRole, project: shop, spec: { key: editor, displayName: Editor } }, \
+              { kind: Project, spec: { name: shop } }]",
+        )
+        .unwrap();
+        let delete = |kind| Change::Delete {
+            kind,
+            label: String::new(),
+            request: Request {
+                org: None,
+                method: Method::DELETE,
+                endpoint: String::new(),
+                body: None,
+            },
+        };
+        let mut changes = [
+            delete(Kind::Project),
+            Change::Create(&manifests[0]),
+            delete(Kind::App),
+            Change::Create(&manifests[1]),
+        ];
+        changes.sort_by_key(Change::order);
+        let order = changes
+            .iter()
+            .map(|change| match change {
+                Change::Delete { kind, .. } => format!("- {}", kind.as_str()),
+                change => format!("+ {}", change.label()),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            order,
+            [
+                "+ project shop",
+                "+ project-role shop/editor",
+                "- app",
+                "- project"
+            ]
+        );
+    }
+}
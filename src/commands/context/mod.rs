@@ -0,0 +1,81 @@
+//! Management of the contexts stored in the configuration directory
+
+use serde_json::{json, Value};
+use tracing::info;
+
+use crate::{
+    context::{is_cached_credentials, Context, ContextStore},
+    error::ZitadelCLIError,
+    output::Output,
+};
+
+/// Prints the contexts in the output format, the current one has `current` set, the secrets are left out
+pub(crate) fn list_contexts(output: &Output) -> Result<(), ZitadelCLIError> {
+    let store = ContextStore::load()?;
+    let contexts: Vec<Value> = store
+        .contexts
+        .iter()
+        .map(|(name, context)| {
+            json!({
+                "name": name,
+                "current": store.current_context.as_deref() == Some(name.as_str()),
+                "issuer": context.issuer,
+                "flow": context.flow,
+                "org": context.org,
+            })
+        })
+        .collect();
+    output.print(&json!({ "result": contexts }))
+}
+
+/// Sets the context used when `--context` isn't specified
+pub(crate) fn use_context(name: &str) -> Result<(), ZitadelCLIError> {
+    let mut store = ContextStore::load()?;
+    if !store.contexts.contains_key(name) {
+        return Err(ZitadelCLIError::Context(format!(
+            "The context {name} doesn't exist"
+        )));
+    }
+    store.current_context = Some(name.to_string());
+    store.save()
+}
+
+/// Adds a context, the first context added becomes the current one
+pub(crate) fn add_context(name: &str, context: Context) -> Result<(), ZitadelCLIError> {
+    let mut store = ContextStore::load()?;
+    if store.contexts.contains_key(name) {
+        return Err(ZitadelCLIError::Context(format!(
+            "The context {name} already exists, remove it first"
+        )));
+    }
+    store.contexts.insert(name.to_string(), context);
+    if store.current_context.is_none() {
+        store.current_context = Some(name.to_string());
+    }
+    store.save()
+}
+
+/// Removes a context and deletes its cached credentials
+/// - A custom credentials file outside of the credentials directory is kept, it may not belong to the context only
+pub(crate) fn remove_context(name: &str) -> Result<(), ZitadelCLIError> {
+    let mut store = ContextStore::load()?;
+    let context = store
+        .contexts
+        .remove(name)
+        .ok_or(ZitadelCLIError::Context(format!(
+            "The context {name} doesn't exist"
+        )))?;
+    if store.current_context.as_deref() == Some(name) {
+        store.current_context = None;
+    }
+    let path = &context.credentials_file_path;
+    if !is_cached_credentials(path)? {
+        info!("The credentials file {} is kept", path.display());
+    } else {
+        match std::fs::remove_file(path) {
+            Err(error) if error.kind() != std::io::ErrorKind::NotFound => return Err(error.into()),
+            _ => (),
+        }
+    }
+    store.save()
+}
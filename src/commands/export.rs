@@ -0,0 +1,232 @@
+//! The `export` command, which writes the configuration of an organization as manifests that `apply` can reproduce
+//! The resources of the organization are written to `org.yaml`, and each project with its roles, applications,
+//! grants and members to its own `project-<name>.yaml`
+
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use tracing::{info, warn};
+
+use crate::{
+    client::ZitadelClient,
+    config::Config,
+    error::ZitadelCLIError,
+    payloads::{
+        application::ApplicationType,
+        idp::IdpType,
+        manifest::{redact, write_manifests, Kind, Manifest},
+        policy::PolicyType,
+    },
+};
+
+use super::{
+    apply::live::{self, LiveResource},
+    API_KEYS,
+};
+
+/// Exports the organization of `--org` to the directory `out`, the secrets are redacted
+pub(crate) async fn handle_export(config: &Config, out: &Path) -> Result<(), ZitadelCLIError> {
+    config.validate(API_KEYS)?;
+    let client = ZitadelClient::new(config).await?;
+    let projects = list(&client, Kind::Project, None).await?;
+    check_unique_names(&projects)?;
+    fs::create_dir_all(out)?;
+
+    let mut manifests = vec![];
+    for idp in list(&client, Kind::Idp, None).await? {
+        let Some(idp_type) = idp.view["type"].as_str().and_then(IdpType::from_api) else {
+            warn!(
+                "The identity provider {} has an unsupported type, skipping it",
+                idp.name
+            );
+            continue;
+        };
+        let mut spec = idp_type.normalize(idp.view)?;
+        redact(&mut spec);
+        manifests.push(Manifest::Idp {
+            org: None,
+            idp_type,
+            spec,
+        });
+    }
+    for policy in list(&client, Kind::Policy, None).await? {
+        let Some(policy_type) = PolicyType::ALL
+            .into_iter()
+            .find(|policy_type| policy_type.as_str() == policy.key)
+        else {
+            continue;
+        };
+        manifests.push(Manifest::Policy {
+            org: None,
+            policy_type,
+            spec: policy_type.normalize(policy.view)?,
+        });
+    }
+    for action in list(&client, Kind::Action, None).await? {
+        manifests.push(Manifest::Action {
+            org: None,
+            spec: serde_json::from_value(action.view).map_err(ZitadelCLIError::InvalidResponse)?,
+        });
+    }
+    for member in list(&client, Kind::Member, None).await? {
+        manifests.push(Manifest::Member {
+            org: None,
+            project: None,
+            spec: serde_json::from_value(member.view).map_err(ZitadelCLIError::InvalidResponse)?,
+        });
+    }
+    let mut count = manifests.len();
+    let mut paths = vec![out.join("org.yaml")];
+    write_manifests(&paths[0], &manifests)?;
+
+    let mut file_names = HashSet::new();
+    for project in projects {
+        // Different names can give the same file name, the id tells apart their files
+        let mut file_name = format!("project-{}.yaml", slug(&project.name));
+        if !file_names.insert(file_name.clone()) {
+            file_name = format!("project-{}-{}.yaml", slug(&project.name), project.id);
+        }
+        let manifests = export_project(&client, project).await?;
+        let path = out.join(file_name);
+        write_manifests(&path, &manifests)?;
+        count += manifests.len();
+        paths.push(path);
+    }
+    info!(
+        "Exported {count} manifests to {}: {}",
+        out.display(),
+        paths
+            .iter()
+            .map(PathBuf::as_path)
+            .map(Path::display)
+            .map(|path| path.to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    Ok(())
+}
+
+/// Returns the manifests of a project and of its roles, applications, grants and members
+async fn export_project(
+    client: &ZitadelClient,
+    project: LiveResource,
+) -> Result<Vec<Manifest>, ZitadelCLIError> {
+    let name = project.name;
+    let project_id = project.id.as_str();
+    let mut manifests = vec![Manifest::Project {
+        org: None,
+        spec: serde_json::from_value(project.view).map_err(ZitadelCLIError::InvalidResponse)?,
+    }];
+    for role in list(client, Kind::ProjectRole, Some(project_id)).await? {
+        manifests.push(Manifest::ProjectRole {
+            org: None,
+            project: name.clone(),
+            spec: serde_json::from_value(role.view).map_err(ZitadelCLIError::InvalidResponse)?,
+        });
+    }
+    for app in list(client, Kind::App, Some(project_id)).await? {
+        let app_type = [
+            ApplicationType::Oidc,
+            ApplicationType::Api,
+            ApplicationType::Saml,
+        ]
+        .into_iter()
+        .find(|app_type| app.view.get(app_type.config_field()).is_some());
+        let Some(app_type) = app_type else {
+            warn!(
+                "The application {} has an unsupported type, skipping it",
+                app.name
+            );
+            continue;
+        };
+        manifests.push(Manifest::App {
+            org: None,
+            project: name.clone(),
+            app_type,
+            spec: app_type.normalize(app.view)?,
+        });
+    }
+    for grant in list(client, Kind::ProjectGrant, Some(project_id)).await? {
+        manifests.push(Manifest::ProjectGrant {
+            org: None,
+            project: name.clone(),
+            granted_org: grant.name,
+            role_keys: serde_json::from_value(grant.view["roleKeys"].clone())
+                .map_err(ZitadelCLIError::InvalidResponse)?,
+        });
+    }
+    for member in list(client, Kind::Member, Some(project_id)).await? {
+        manifests.push(Manifest::Member {
+            org: None,
+            project: Some(name.clone()),
+            spec: serde_json::from_value(member.view).map_err(ZitadelCLIError::InvalidResponse)?,
+        });
+    }
+    Ok(manifests)
+}
+
+/// Lists the live resources of a kind sorted by key, so the exports of the same configuration are identical
+async fn list(
+    client: &ZitadelClient,
+    kind: Kind,
+    project_id: Option<&str>,
+) -> Result<Vec<LiveResource>, ZitadelCLIError> {
+    let mut resources = live::list(client, kind, project_id).await?;
+    resources.sort_by(|a, b| a.key.cmp(&b.key));
+    Ok(resources)
+}
+
+/// Checks that no two projects have the same name, `apply` matches the projects of the manifests by name
+/// so an export of projects with the same name couldn't be reproduced
+/// - Returns `Err(ZitadelCLIError::Manifest(error))` naming the first duplicated project, nothing is written then
+fn check_unique_names(projects: &[LiveResource]) -> Result<(), ZitadelCLIError> {
+    let mut names = HashSet::new();
+    for project in projects {
+        if !names.insert(project.name.as_str()) {
+            return Err(ZitadelCLIError::Manifest(format!(
+                "several projects are named {}, rename them so the export can be applied",
+                project.name
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Returns `name` in lowercase with the characters other than letters and digits replaced by dashes
+fn slug(name: &str) -> String {
+    name.to_lowercase()
+        .split(|character: char| !character.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    use super::*;
+
+    #[test]
+    fn test_slug() {
+        assert_eq!(slug("My Shop (staging)"), "my-shop-staging");
+    }
+
+    #[test]
+    fn test_check_unique_names() {
+        let project = |id: &str, name: &str| LiveResource {
+            id: id.to_string(),
+            key: name.to_string(),
+            name: name.to_string(),
+            view: Value::Null,
+        };
+        assert!(check_unique_names(&[project("1", "shop"), project("2", "Shop")]).is_ok());
+        assert!(matches!(
+            check_unique_names(&[project("1", "shop"), project("2", "shop")]),
+            Err(ZitadelCLIError::Manifest(message)) if message.contains("shop")
+        ));
+    }
+}
@@ -0,0 +1,526 @@
+//! The `human-user import` command, which creates the human users of a CSV or JSONL file
+//! Every row is validated before any user is created, the users are then created concurrently and the outcome of
+//! each row is written to a JSONL report, which `--resume` reads to skip the users already created
+//! The password hashes of `hashedPassword.hash` are checked so the migrated users keep their passwords
+
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use base64::{engine::general_purpose, Engine};
+use futures::{stream, StreamExt};
+use reqwest::Method;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tracing::{info, warn};
+
+use crate::{
+    client::ZitadelClient,
+    config::Config,
+    error::ZitadelCLIError,
+    payloads::{hashed_password::HashFormat, user::NewHumanUser},
+};
+
+use super::API_KEYS;
+
+/// The number of users created at the same time if `--concurrency` is missing
+pub(crate) const DEFAULT_CONCURRENCY: u16 = 8;
+/// The fields of the CSV columns parsed as booleans, the other fields are strings
+const BOOLEAN_FIELDS: [&str; 2] = ["isVerified", "changeRequired"];
+/// The prefix of the CSV columns added to the metadata of the user, e.g. `metadata.legacyId`
+const METADATA_PREFIX: &str = "metadata.";
+
+/// The options of an import
+pub(crate) struct ImportOptions<'a> {
+    /// The CSV columns mapped to a field of the payload, as `column=field`, the other columns are named after their
+    /// field (e.g. `profile.givenName`)
+    pub(crate) mappings: &'a [String],
+    /// The number of users created at the same time
+    pub(crate) concurrency: usize,
+    /// Creates the valid users even if some rows are invalid or rejected, otherwise nothing is created if a row is
+    /// invalid and the import stops at the first rejected user
+    pub(crate) continue_on_error: bool,
+    /// The report written, `<file>.report.jsonl` if missing
+    pub(crate) report: Option<&'a Path>,
+    /// The report of a previous import of the same file, the users it created are skipped
+    pub(crate) resume: Option<&'a Path>,
+    /// The algorithm of the password hashes, every row must then have a hash of this algorithm
+    pub(crate) hash_format: Option<HashFormat>,
+}
+
+/// A row of the imported file
+struct Row {
+    /// The line of the row in the file, which identifies it in the report
+    line: u64,
+    /// The username of the user, or its email if missing
+    user: Option<String>,
+    /// The payload of the user, or why the row couldn't be read
+    payload: Result<Value, String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+/// The outcome of a row, the report holds one per line
+struct ReportEntry {
+    line: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    user: Option<String>,
+    /// The id of the created user
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    user_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// The JSONL report of an import, each entry is flushed as soon as it is written
+struct Report {
+    path: PathBuf,
+    writer: BufWriter<File>,
+}
+
+impl Report {
+    fn create(path: PathBuf) -> Result<Report, ZitadelCLIError> {
+        let writer = BufWriter::new(File::create(&path)?);
+        Ok(Report { path, writer })
+    }
+
+    fn write(&mut self, entry: &ReportEntry) -> Result<(), ZitadelCLIError> {
+        serde_json::to_writer(&mut self.writer, entry)?;
+        self.writer.write_all(b"\n")?;
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+/// Creates the human users of `file`, a `.csv` file with a header row or a `.jsonl` file with one payload per line
+pub(crate) async fn handle_import(
+    config: &Config,
+    file: &Path,
+    options: &ImportOptions<'_>,
+) -> Result<(), ZitadelCLIError> {
+    config.validate(API_KEYS)?;
+    let continue_on_error = options.continue_on_error;
+    let rows = read_rows(file, &parse_mappings(options.mappings)?)?;
+    let created = match options.resume {
+        Some(resume) => read_created(resume)?,
+        None => HashMap::new(),
+    };
+
+    let mut skipped = vec![];
+    let mut invalid = vec![];
+    let mut pending = vec![];
+    for row in rows {
+        if let Some(entry) = created.get(&row.line) {
+            if entry.user != row.user {
+                return Err(ZitadelCLIError::Import(format!(
+                    "line {} is {} in the report but {} in {}, the file changed since the report was written",
+                    row.line,
+                    entry.user.as_deref().unwrap_or("-"),
+                    row.user.as_deref().unwrap_or("-"),
+                    file.display()
+                )));
+            }
+            skipped.push(row.line);
+            continue;
+        }
+        match row
+            .payload
+            .and_then(|payload| validate(payload, options.hash_format))
+        {
+            Ok(payload) => pending.push((row.line, row.user, payload)),
+            Err(error) => {
+                warn!("Line {}: {error}", row.line);
+                invalid.push(ReportEntry {
+                    line: row.line,
+                    user: row.user,
+                    user_id: None,
+                    error: Some(error),
+                });
+            }
+        }
+    }
+
+    let mut report = Report::create(
+        options
+            .report
+            .map_or_else(|| file.with_extension("report.jsonl"), Path::to_path_buf),
+    )?;
+    for line in &skipped {
+        report.write(&created[line])?;
+    }
+    for entry in &invalid {
+        report.write(entry)?;
+    }
+    if !invalid.is_empty() && !continue_on_error {
+        return Err(ZitadelCLIError::Import(format!(
+            "{} rows are invalid, no user was created, see {}",
+            invalid.len(),
+            report.path.display()
+        )));
+    }
+
+    let client = ZitadelClient::new(config).await?;
+    let stop = AtomicBool::new(false);
+    let mut results = stream::iter(pending)
+        .map(|(line, user, payload)| {
+            let client = &client;
+            let stop = &stop;
+            async move {
+                // The users of the rows following a rejected one are not created, the report allows to resume
+                if stop.load(Ordering::Relaxed) {
+                    return None;
+                }
+                let result = client
+                    .request(Method::POST, "/v2/users/human", Some(&payload))
+                    .await;
+                Some((line, user, result))
+            }
+        })
+        .buffer_unordered(options.concurrency.max(1));
+    let mut created = 0;
+    let mut failed = 0;
+    let mut first_error = None;
+    while let Some(outcome) = results.next().await {
+        let Some((line, user, result)) = outcome else {
+            continue;
+        };
+        let mut entry = ReportEntry {
+            line,
+            user,
+            user_id: None,
+            error: None,
+        };
+        match result {
+            Ok(response) => {
+                created += 1;
+                entry.user_id = response["userId"].as_str().map(str::to_string);
+            }
+            Err(error) => {
+                warn!("Line {line}: {error}");
+                failed += 1;
+                entry.error = Some(error.to_string());
+                if !continue_on_error {
+                    stop.store(true, Ordering::Relaxed);
+                    first_error.get_or_insert(error);
+                }
+            }
+        }
+        report.write(&entry)?;
+    }
+
+    info!(
+        "{created} users created, {} skipped, {} invalid and {failed} rejected, the report is written to {}",
+        skipped.len(),
+        invalid.len(),
+        report.path.display()
+    );
+    config.output.print(&json!({
+        "created": created,
+        "skipped": skipped.len(),
+        "invalid": invalid.len(),
+        "failed": failed,
+        "report": report.path,
+    }))?;
+    if let Some(error) = first_error {
+        return Err(error);
+    }
+    match invalid.len() + failed {
+        0 => Ok(()),
+        count => Err(ZitadelCLIError::Import(format!(
+            "{count} rows were not imported, see {}",
+            report.path.display()
+        ))),
+    }
+}
+
+/// Parses the `column=field` mappings of `--map`
+/// - Returns `Err(ZitadelCLIError::Config(errors))` with the mappings without `=`
+fn parse_mappings(mappings: &[String]) -> Result<HashMap<String, String>, ZitadelCLIError> {
+    let (valid, invalid): (Vec<_>, Vec<_>) = mappings
+        .iter()
+        .map(|mapping| mapping.split_once('=').ok_or(mapping))
+        .partition(Result::is_ok);
+    if !invalid.is_empty() {
+        return Err(ZitadelCLIError::Config(
+            invalid
+                .into_iter()
+                .filter_map(Result::err)
+                .map(|mapping| format!("--map {mapping} isn't formatted as column=field"))
+                .collect(),
+        ));
+    }
+    Ok(valid
+        .into_iter()
+        .flatten()
+        .map(|(column, field)| (column.trim().to_string(), field.trim().to_string()))
+        .collect())
+}
+
+/// Reads the rows of a `.csv` or `.jsonl` file
+/// - Returns `Err(ZitadelCLIError::Import(error))` if the file has another extension or its header can't be read
+fn read_rows(file: &Path, mappings: &HashMap<String, String>) -> Result<Vec<Row>, ZitadelCLIError> {
+    let content = fs::read_to_string(file)?;
+    match file.extension().and_then(|extension| extension.to_str()) {
+        Some("csv") => read_csv(&content, mappings)
+            .map_err(|error| ZitadelCLIError::Import(format!("{}: {error}", file.display()))),
+        Some("jsonl" | "ndjson") => Ok(read_jsonl(&content)),
+        _ => Err(ZitadelCLIError::Import(format!(
+            "{} isn't a .csv or .jsonl file",
+            file.display()
+        ))),
+    }
+}
+
+/// Reads the rows of a CSV file, each column is mapped to a field of the payload
+fn read_csv(content: &str, mappings: &HashMap<String, String>) -> Result<Vec<Row>, String> {
+    let mut reader = csv::Reader::from_reader(content.as_bytes());
+    let headers = reader.headers().map_err(|error| error.to_string())?.clone();
+    if let Some(column) = mappings
+        .keys()
+        .find(|column| !headers.iter().any(|header| header == column.as_str()))
+    {
+        return Err(format!("the mapped column {column} doesn't exist"));
+    }
+    // The columns mapped to an empty field are ignored
+    let fields: Vec<Option<&str>> = headers
+        .iter()
+        .map(|column| match mappings.get(column) {
+            Some(field) => (!field.is_empty()).then_some(field.as_str()),
+            None => Some(column),
+        })
+        .collect();
+    Ok(reader
+        .records()
+        .map(|record| match record {
+            Ok(record) => {
+                let line = record
+                    .position()
+                    .map(|position| position.line())
+                    .unwrap_or_default();
+                row(line, csv_payload(&fields, &record))
+            }
+            Err(error) => Row {
+                line: error
+                    .position()
+                    .map(|position| position.line())
+                    .unwrap_or_default(),
+                user: None,
+                payload: Err(error.to_string()),
+            },
+        })
+        .collect())
+}
+
+/// Returns the payload of a CSV record, the empty cells are skipped
+fn csv_payload(fields: &[Option<&str>], record: &csv::StringRecord) -> Result<Value, String> {
+    let mut payload = json!({});
+    for (field, cell) in fields.iter().zip(record.iter()) {
+        let (Some(field), cell) = (field, cell.trim()) else {
+            continue;
+        };
+        if cell.is_empty() {
+            continue;
+        }
+        if let Some(key) = field.strip_prefix(METADATA_PREFIX) {
+            let metadata = json!({ "key": key, "value": general_purpose::STANDARD.encode(cell) });
+            match payload["metadata"].as_array_mut() {
+                Some(entries) => entries.push(metadata),
+                None => payload["metadata"] = json!([metadata]),
+            }
+            continue;
+        }
+        let name = field.rsplit('.').next().unwrap_or(field);
+        let value = match BOOLEAN_FIELDS.contains(&name) {
+            true => match cell.to_lowercase().as_str() {
+                "true" => Value::Bool(true),
+                "false" => Value::Bool(false),
+                _ => return Err(format!("{field} must be true or false, not {cell}")),
+            },
+            false => Value::String(cell.to_string()),
+        };
+        set(&mut payload, field, value)?;
+    }
+    Ok(payload)
+}
+
+/// Sets the nested field of `payload` at the dotted path `field`, e.g. `profile.givenName`
+fn set(payload: &mut Value, field: &str, value: Value) -> Result<(), String> {
+    let mut target = payload;
+    for part in field.split('.') {
+        let Some(object) = target.as_object_mut() else {
+            return Err(format!("{field} is set by two columns"));
+        };
+        target = object.entry(part).or_insert(json!({}));
+    }
+    *target = value;
+    Ok(())
+}
+
+/// Reads the rows of a JSONL file, each non empty line is the payload of a user
+fn read_jsonl(content: &str) -> Vec<Row> {
+    content
+        .lines()
+        .zip(1..)
+        .filter(|(line, _)| !line.trim().is_empty())
+        .map(|(line, number)| {
+            row(
+                number,
+                serde_json::from_str(line).map_err(|error| error.to_string()),
+            )
+        })
+        .collect()
+}
+
+fn row(line: u64, payload: Result<Value, String>) -> Row {
+    let user = payload.as_ref().ok().and_then(|payload| {
+        payload
+            .get("username")
+            .or_else(|| payload.pointer("/email/email"))
+            .and_then(Value::as_str)
+            .map(str::to_string)
+    });
+    Row {
+        line,
+        user,
+        payload,
+    }
+}
+
+/// Checks that the payload is a valid human user, without fields missing from [`NewHumanUser`], and that its password
+/// hash is accepted by Zitadel and of the algorithm `hash_format` if specified
+fn validate(payload: Value, hash_format: Option<HashFormat>) -> Result<Value, String> {
+    let user: NewHumanUser =
+        serde_json::from_value(payload.clone()).map_err(|error| error.to_string())?;
+    let normalized = serde_json::to_value(user).map_err(|error| error.to_string())?;
+    if let Some(field) = unknown_fields(&payload, &normalized).first() {
+        return Err(format!("unknown field {field}"));
+    }
+    if !normalized["email"]["email"]
+        .as_str()
+        .is_some_and(|email| email.contains('@'))
+    {
+        return Err("email.email isn't an email address".to_string());
+    }
+    let hash = normalized["hashedPassword"]["hash"].as_str();
+    if hash.is_some() && !normalized["password"].is_null() {
+        return Err("password and hashedPassword can't both be set".to_string());
+    }
+    match (hash.map(HashFormat::detect).transpose()?, hash_format) {
+        (Some(format), Some(expected)) if format != expected => Err(format!(
+            "hashedPassword.hash is hashed with {format}, not {expected}"
+        )),
+        (None, Some(expected)) => Err(format!(
+            "hashedPassword.hash is missing, a password hashed with {expected} is expected"
+        )),
+        _ => Ok(normalized),
+    }
+}
+
+/// Returns the dotted paths of the fields of `payload` dropped by its normalization, i.e. unknown to the payload
+fn unknown_fields(payload: &Value, normalized: &Value) -> Vec<String> {
+    let Some(fields) = payload.as_object() else {
+        return vec![];
+    };
+    fields
+        .iter()
+        .filter(|(_, value)| !value.is_null())
+        .flat_map(|(key, value)| match normalized.get(key) {
+            None | Some(Value::Null) => vec![key.clone()],
+            Some(normalized) => unknown_fields(value, normalized)
+                .into_iter()
+                .map(|field| format!("{key}.{field}"))
+                .collect(),
+        })
+        .collect()
+}
+
+/// Reads the entries of the users created by a previous import, by line
+fn read_created(path: &Path) -> Result<HashMap<u64, ReportEntry>, ZitadelCLIError> {
+    let content = fs::read_to_string(path)?;
+    let mut created = HashMap::new();
+    for line in content.lines().filter(|line| !line.trim().is_empty()) {
+        let entry: ReportEntry = serde_json::from_str(line)?;
+        if entry.user_id.is_some() {
+            created.insert(entry.line, entry);
+        }
+    }
+    Ok(created)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_csv() {
+        let content = "\
+first_name,profile.familyName,email.email,email.isVerified,notes,metadata.legacyId
+Minnie,Mouse,minnie@example.com,true,vip,42
+Mickey,Mouse,mickey@example.com,maybe,,
+";
+        let mappings = parse_mappings(&[
+            "first_name=profile.givenName".to_string(),
+            "notes=".to_string(),
+        ])
+        .unwrap();
+        let rows = read_csv(content, &mappings).unwrap();
+        assert_eq!(rows[0].line, 2);
+        assert_eq!(rows[0].user.as_deref(), Some("minnie@example.com"));
+        assert_eq!(
+            rows[0].payload,
+            Ok(json!({
+                "profile": { "givenName": "Minnie", "familyName": "Mouse" },
+                "email": { "email": "minnie@example.com", "isVerified": true },
+                "metadata": [{ "key": "legacyId", "value": "NDI=" }]
+            }))
+        );
+        assert_eq!(
+            rows[1].payload,
+            Err("email.isVerified must be true or false, not maybe".to_string())
+        );
+        assert!(parse_mappings(&["first_name".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_validate() {
+        let user = json!({
+            "profile": { "givenName": "Minnie", "familyName": "Mouse" },
+            "email": { "email": "minnie@example.com" }
+        });
+        assert!(validate(user.clone(), None).is_ok());
+        assert_eq!(
+            validate(user.clone(), Some(HashFormat::Bcrypt)),
+            Err(
+                "hashedPassword.hash is missing, a password hashed with bcrypt is expected"
+                    .to_string()
+            )
+        );
+
+        let mut hashed = user.clone();
+        hashed["hashedPassword"] = json!({ "hash": "$1$saltsalt$qjXMvbEw8oaL.CzflDtaK/" });
+        assert!(validate(hashed.clone(), Some(HashFormat::Md5Crypt)).is_ok());
+        assert_eq!(
+            validate(hashed.clone(), Some(HashFormat::Bcrypt)),
+            Err("hashedPassword.hash is hashed with MD5-crypt, not bcrypt".to_string())
+        );
+        hashed["hashedPassword"]["hash"] = json!("5f4dcc3b5aa765d61d8327deb882cf99");
+        assert!(validate(hashed, None).is_err());
+
+        let mut typo = user.clone();
+        typo["profile"]["nickNme"] = json!("Min");
+        assert_eq!(
+            validate(typo, None),
+            Err("unknown field profile.nickNme".to_string())
+        );
+
+        let mut missing = user;
+        missing["profile"] = json!({ "givenName": "Minnie" });
+        assert!(validate(missing, None)
+            .unwrap_err()
+            .contains("missing field `familyName`"));
+    }
+}
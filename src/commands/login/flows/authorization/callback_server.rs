@@ -13,7 +13,7 @@ use serde::Deserialize;
 
 use crate::{
     commands::login::flows::{authorization::exchange_code, save_config},
-    env::{callback_server_address, client_id, config_file_path, is_secure, issuer, scopes},
+    config::Config,
     error::ZitadelCLIError,
 };
 
@@ -72,7 +72,7 @@ async fn callback(
     match result {
         Ok(_) => {
             stop_handle.stop(true);
-            format!("Code received and access_token retrieved, you can close this page.")
+            "Code received and access_token retrieved, you can close this page.".to_string()
         }
         Err(err) => {
             error!("An error occured: {}", err);
@@ -81,20 +81,22 @@ async fn callback(
     }
 }
 
-/// Initializes the configuration from the environment variables, here is the full list:
+/// Initializes the flow configuration from the configuration, here is the full list of keys used:
+/// - `CONFIG_FILE_PATH` is the path of the file the access token is written to
 /// - `ISSUER` The url of the Zitadel instance
 /// - `CLIENT_ID` is the client id of the application
 /// - `CALLBACK_SERVER_ADDRESS` is the address of the server that listens for the callback
 /// - `SCOPES` is the scopes to request from the OpenID Connect provider
 /// - `IS_SECURE` is a boolean that indicates if the server is secure
+///
 /// Returns the configuration of the application
-pub(crate) fn init_config_from_env() -> Result<AuthorizationFlowAppConfig, ZitadelCLIError> {
-    let config_file_path = config_file_path();
-    let issuer = issuer();
-    let client_id = client_id();
-    let callback_server_address = callback_server_address();
-    let scopes = scopes();
-    let is_secure = is_secure();
+pub(crate) fn init_config(config: &Config) -> Result<AuthorizationFlowAppConfig, ZitadelCLIError> {
+    let config_file_path = config.config_file_path()?;
+    let issuer = config.issuer()?;
+    let client_id = config.client_id()?;
+    let callback_server_address = config.callback_server_address()?;
+    let scopes = config.scopes()?;
+    let is_secure = config.is_secure();
     let code_verifier = generate_code_verifier();
     let code_challenge = generate_code_challenge(code_verifier.clone());
 
@@ -2,20 +2,27 @@ pub mod callback_server;
 
 use std::path::PathBuf;
 
-use crate::{commands::discover, error::ZitadelCLIError};
+use crate::{
+    client::http_client,
+    commands::{discover, login::unix_timestamp, Token},
+    config::{Config, ConfigKey},
+    error::ZitadelCLIError,
+};
 
 use base64::{
     alphabet,
     engine::{self, general_purpose},
     Engine,
 };
-use callback_server::{init_callback_server, init_config_from_env};
+use callback_server::{init_callback_server, init_config};
 use rand::{distributions::Alphanumeric, thread_rng, Rng};
 use reqwest::Url;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use tracing::{error, info};
 
+use super::save_config;
+
 pub(crate) struct AuthorizationFlowAppConfig {
     config_file_path: PathBuf,
     issuer: String,
@@ -39,10 +46,11 @@ impl AuthorizationFlowAppConfig {
 /// This is the main function of the module, each flow implemented has to have a login function
 ///  Generates the url to log in by using credentials, starts the callback server that writes the token to the file specified in the `CONFIG_FILE_PATH` environment variable
 /// - `open_browser`: Whether to open the browser automatically
+/// - `config`: The configuration of the CLI
 /// - Returns `Ok(())` if the login was successful
 /// - Returns `Err(ZitadelCLIError::IO(error))` if the login was not successful
-pub async fn login(open_browser: bool) -> Result<(), ZitadelCLIError> {
-    let config: AuthorizationFlowAppConfig = init_config_from_env().unwrap();
+pub async fn login(open_browser: bool, config: &Config) -> Result<(), ZitadelCLIError> {
+    let config: AuthorizationFlowAppConfig = init_config(config)?;
     let discover_config =
         discover(&format! {"{}/.well-known/openid-configuration",&config.issuer}).await?;
     let url = generate_signin_url(
@@ -81,6 +89,10 @@ pub struct CodeResponse {
     pub scope: Option<String>,
     pub refresh_token: Option<String>,
     pub token_type: String,
+    /// Unix timestamp at which the token was issued, not sent by the provider but stored alongside the token
+    /// to be able to tell when it expires
+    #[serde(default)]
+    pub issued_at: Option<u64>,
 }
 
 /// Exchanges the `code` returned by the authorize endpoint for an `access_token`
@@ -110,18 +122,81 @@ pub async fn exchange_code(
             ("code_challenge", code_challenge),
         ],
     )?;
-    let response = reqwest::get(url).await?;
+    let response = http_client()?.get(url).send().await?;
     match response.status() {
         reqwest::StatusCode::OK => {
-            let parsed_response = response.json::<CodeResponse>().await?;
+            let mut parsed_response = response.json::<CodeResponse>().await?;
+            parsed_response.issued_at = Some(unix_timestamp());
             Ok(parsed_response)
         }
-        _ => {
-            return Err(ZitadelCLIError::ReqwestResponse(response.text().await?));
+        status => Err(ZitadelCLIError::OAuth(Some(status), response.text().await?)),
+    }
+}
+
+/// Exchanges a `refresh_token` for a new `access_token`
+/// `token_endpoint` is the token endpoint of the OpenID Connect provider
+/// `client_id` is the client id of the application
+/// `refresh_token` is the refresh token returned alongside the previous `access_token`
+/// Returns the new `access_token` and other information, the previous `refresh_token` is kept if the provider
+/// did not issue a new one
+pub async fn exchange_refresh_token(
+    token_endpoint: &str,
+    client_id: &str,
+    refresh_token: &str,
+) -> Result<CodeResponse, ZitadelCLIError> {
+    let client = http_client()?;
+    let response = client
+        .post(token_endpoint)
+        .form(&[
+            ("grant_type", "refresh_token"),
+            ("refresh_token", refresh_token),
+            ("client_id", client_id),
+        ])
+        .send()
+        .await?;
+    match response.status() {
+        reqwest::StatusCode::OK => {
+            let mut parsed_response = response.json::<CodeResponse>().await?;
+            parsed_response.issued_at = Some(unix_timestamp());
+            if parsed_response.refresh_token.is_none() {
+                parsed_response.refresh_token = Some(refresh_token.to_string());
+            }
+            Ok(parsed_response)
         }
+        status => Err(ZitadelCLIError::OAuth(
+            Some(status),
+            format!(
+                "Failed to refresh the access token, please log in again: {}",
+                response.text().await?,
+            ),
+        )),
     }
 }
 
+/// Renews the access token stored in the file specified by `CONFIG_FILE_PATH`
+/// by using its `refresh_token` against the discovered `token_endpoint`, then writes the new token to the same file
+/// - `config` is the configuration of the CLI, `CLIENT_ID` is required on top of the keys needed by every command
+/// - `refresh_token` is the refresh token of the expired access token
+/// - Returns the renewed token
+pub async fn refresh(config: &Config, refresh_token: &str) -> Result<Token, ZitadelCLIError> {
+    config.validate(&[ConfigKey::ClientId])?;
+    let discover_config =
+        discover(&format! {"{}/.well-known/openid-configuration", config.issuer()?}).await?;
+    let code_response = exchange_refresh_token(
+        &discover_config.token_endpoint,
+        &config.client_id()?,
+        refresh_token,
+    )
+    .await?;
+    save_config(&config.config_file_path()?, &code_response)?;
+    Ok(Token {
+        access_token: code_response.access_token,
+        expires_in: Some(code_response.expires_in),
+        issued_at: code_response.issued_at,
+        refresh_token: code_response.refresh_token,
+    })
+}
+
 /// Generates a random string of length between 43 and 128, used to generate the `code_challenge`
 /// Returns the generated string
 pub fn generate_code_verifier() -> String {
@@ -152,6 +227,7 @@ pub fn generate_code_challenge(code_verifier: String) -> String {
 /// - `redirect_uri` is the uri to redirect to after the sign in
 /// - `scope` is the scope of the request
 /// - `code_challenge` is the code challenge generated from the `code_verifier`
+///
 /// Returns the generated URL
 pub fn generate_signin_url(
     base_url: &str,
@@ -160,7 +236,7 @@ pub fn generate_signin_url(
     scope: &str,
     code_challenge: &str,
 ) -> Url {
-    let mut url = Url::parse(&base_url).unwrap();
+    let mut url = Url::parse(base_url).unwrap();
     url.query_pairs_mut()
         .append_pair("client_id", client_id)
         .append_pair("redirect_uri", redirect_uri)
@@ -3,11 +3,7 @@ use std::{collections::HashMap, path::PathBuf};
 use reqwest::StatusCode;
 use serde::Serialize;
 
-use crate::{
-    commands::discover,
-    env::{client_id, client_secret, config_file_path, issuer, scopes},
-    error::ZitadelCLIError,
-};
+use crate::{client::http_client, commands::discover, config::Config, error::ZitadelCLIError};
 
 use super::save_config;
 
@@ -28,8 +24,8 @@ struct ClientCredentialsResponse {
 
 /// Logs the user in using the client credential flow
 /// Writes the access token to the config file
-pub(crate) async fn login() -> Result<(), ZitadelCLIError> {
-    let config: ClientCredentialFlowAppConfig = init_config_from_env();
+pub(crate) async fn login(config: &Config) -> Result<(), ZitadelCLIError> {
+    let config: ClientCredentialFlowAppConfig = init_config(config)?;
     let discover_config =
         discover(&format! {"{}/.well-known/openid-configuration", config.issuer}).await?;
     let url = discover_config.token_endpoint;
@@ -40,7 +36,7 @@ pub(crate) async fn login() -> Result<(), ZitadelCLIError> {
     form_data.insert("client_secret", &config.client_secret);
     form_data.insert("scope", &config.scopes);
 
-    let client = reqwest::Client::new();
+    let client = http_client()?;
     let response = client
         .post(url)
         .form(&form_data)
@@ -52,36 +48,35 @@ pub(crate) async fn login() -> Result<(), ZitadelCLIError> {
             let parsed_response = response.json::<ClientCredentialsResponse>().await?;
             save_config(&config.config_file_path, parsed_response)
         }
-        _ => {
-            return Err(ZitadelCLIError::ReqwestResponse(format!(
-                "An unexpected error occured: {}",
-                response.text().await?,
-            )))
-        }
+        status => Err(ZitadelCLIError::OAuth(
+            Some(status),
+            format!("An unexpected error occured: {}", response.text().await?),
+        )),
     }
 }
 
-/// Initializes the configuration from the environment variables, here is the full list:
-/// - `DISCOVERY_ENDPOINT` is the endpoint to discover the OpenID Connect provider
+/// Initializes the flow configuration from the configuration, here is the full list of keys used:
+/// - `CONFIG_FILE_PATH` is the path of the file the access token is written to
+/// - `ISSUER` The url of the Zitadel instance
 /// - `CLIENT_ID` is the client id of the application
-/// - `CALLBACK_SERVER_ADDRESS` is the address of the server that listens for the callback
+/// - `CLIENT_SECRET` is the client secret of the application
 /// - `SCOPES` is the scopes to request from the OpenID Connect provider
-/// - `IS_SECURE` is a boolean that indicates if the server is secure
+///
 /// Returns the configuration of the application
-fn init_config_from_env() -> ClientCredentialFlowAppConfig {
-    let config_file_path = config_file_path();
-    let issuer = issuer();
-    let client_id: String = client_id();
-    let client_secret = client_secret();
-    let scopes = scopes();
+fn init_config(config: &Config) -> Result<ClientCredentialFlowAppConfig, ZitadelCLIError> {
+    let config_file_path = config.config_file_path()?;
+    let issuer = config.issuer()?;
+    let client_id: String = config.client_id()?;
+    let client_secret = config.client_secret()?;
+    let scopes = config.scopes()?;
 
-    ClientCredentialFlowAppConfig {
+    Ok(ClientCredentialFlowAppConfig {
         config_file_path,
         issuer,
         client_id,
         scopes,
         client_secret,
-    }
+    })
 }
 
 #[cfg(test)]
@@ -96,7 +91,7 @@ mod tests {
         std::env::set_var("CLIENT_SECRET", "client_secret");
         std::env::set_var("SCOPES", "openid profile email");
 
-        let config = super::init_config_from_env();
+        let config = super::init_config(&crate::env::config()).unwrap();
         assert_eq!(config.config_file_path, Path::new("/tmp/config.json"));
         assert_eq!(config.issuer, "https://issuer.com");
         assert_eq!(config.client_id, "client_id");
@@ -0,0 +1,243 @@
+//! Implementation of the [Device Authorization Grant](https://www.rfc-editor.org/rfc/rfc8628)
+//! The user code and verification uri are printed so the user can sign in from any device with a browser,
+//! which makes it usable on machines where the callback server of the `Authorization Code Flow` is unreachable
+
+use std::{path::PathBuf, time::Duration};
+
+use reqwest::StatusCode;
+use serde::Deserialize;
+use tracing::{error, info};
+
+use crate::{
+    client::http_client,
+    commands::{discover, login::unix_timestamp},
+    config::Config,
+    error::ZitadelCLIError,
+};
+
+use super::{authorization::CodeResponse, save_config};
+
+const DEVICE_CODE_GRANT_TYPE: &str = "urn:ietf:params:oauth:grant-type:device_code";
+/// Interval used when the provider doesn't specify one, as recommended by the RFC
+const DEFAULT_POLLING_INTERVAL_SECONDS: u64 = 5;
+/// Number of seconds added to the polling interval every time the provider answers `slow_down`
+const SLOW_DOWN_INCREMENT_SECONDS: u64 = 5;
+
+struct DeviceCodeFlowAppConfig {
+    config_file_path: PathBuf,
+    issuer: String,
+    client_id: String,
+    scopes: String,
+}
+
+#[derive(Debug, Deserialize)]
+/// The response of the device authorization endpoint
+struct DeviceAuthorizationResponse {
+    device_code: String,
+    user_code: String,
+    verification_uri: String,
+    verification_uri_complete: Option<String>,
+    expires_in: u64,
+    interval: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+/// The error returned by the token endpoint while the user hasn't completed the sign in yet
+struct TokenErrorResponse {
+    error: String,
+    error_description: Option<String>,
+}
+
+/// Logs the user in using the device authorization grant
+/// Prints the user code and the verification uri, then polls the token endpoint until the user signed in
+/// Writes the access token to the config file
+/// - `open_browser`: Whether to open the verification uri in the browser automatically
+pub(crate) async fn login(open_browser: bool, config: &Config) -> Result<(), ZitadelCLIError> {
+    let config = init_config(config)?;
+    let discover_config =
+        discover(&format! {"{}/.well-known/openid-configuration", config.issuer}).await?;
+    let device_authorization_endpoint =
+        discover_config
+            .device_authorization_endpoint
+            .ok_or(ZitadelCLIError::OAuth(
+                None,
+                "The provider doesn't expose a device authorization endpoint".to_string(),
+            ))?;
+
+    let device_authorization =
+        request_device_authorization(&device_authorization_endpoint, &config).await?;
+    let verification_uri = device_authorization
+        .verification_uri_complete
+        .clone()
+        .unwrap_or(device_authorization.verification_uri.clone());
+    info! {"Open {} in a browser and enter the code {}", device_authorization.verification_uri, device_authorization.user_code};
+    if open_browser {
+        if let Err(error) = open::that(&verification_uri) {
+            error! {"Failed to open the url: {error}, please open your browser and navigate to {verification_uri}"};
+        }
+    }
+
+    let code_response = poll_token_endpoint(
+        &discover_config.token_endpoint,
+        &config.client_id,
+        &device_authorization,
+    )
+    .await?;
+    save_config(&config.config_file_path, code_response)
+}
+
+/// Requests a device code and a user code from the device authorization endpoint
+async fn request_device_authorization(
+    device_authorization_endpoint: &str,
+    config: &DeviceCodeFlowAppConfig,
+) -> Result<DeviceAuthorizationResponse, ZitadelCLIError> {
+    let client = http_client()?;
+    let response = client
+        .post(device_authorization_endpoint)
+        .form(&[
+            ("client_id", config.client_id.as_str()),
+            ("scope", config.scopes.as_str()),
+        ])
+        .send()
+        .await?;
+    match response.status() {
+        StatusCode::OK => Ok(response.json::<DeviceAuthorizationResponse>().await?),
+        status => Err(ZitadelCLIError::OAuth(
+            Some(status),
+            format!(
+                "Failed to request a device code: {}",
+                response.text().await?,
+            ),
+        )),
+    }
+}
+
+/// Polls the token endpoint until the user completed the sign in, denied it or the device code expired
+/// The polling interval is increased every time the provider answers `slow_down` or a server error
+async fn poll_token_endpoint(
+    token_endpoint: &str,
+    client_id: &str,
+    device_authorization: &DeviceAuthorizationResponse,
+) -> Result<CodeResponse, ZitadelCLIError> {
+    let client = http_client()?;
+    let mut interval = device_authorization
+        .interval
+        .unwrap_or(DEFAULT_POLLING_INTERVAL_SECONDS);
+    let expires_at = unix_timestamp() + device_authorization.expires_in;
+
+    while unix_timestamp() < expires_at {
+        tokio::time::sleep(Duration::from_secs(interval)).await;
+        let response = client
+            .post(token_endpoint)
+            .form(&[
+                ("grant_type", DEVICE_CODE_GRANT_TYPE),
+                ("device_code", device_authorization.device_code.as_str()),
+                ("client_id", client_id),
+            ])
+            .send()
+            .await?;
+        if response.status() == StatusCode::OK {
+            let mut code_response = response.json::<CodeResponse>().await?;
+            code_response.issued_at = Some(unix_timestamp());
+            return Ok(code_response);
+        }
+        let status = response.status();
+        match poll_outcome(status, &response.text().await?)? {
+            PollOutcome::Pending => (),
+            PollOutcome::SlowDown => interval += SLOW_DOWN_INCREMENT_SECONDS,
+        }
+    }
+    Err(ZitadelCLIError::OAuth(
+        None,
+        "The device code expired before the sign in was completed".to_string(),
+    ))
+}
+
+#[derive(Debug, PartialEq, Eq)]
+/// What to do after the token endpoint answered without a token
+enum PollOutcome {
+    /// The user hasn't completed the sign in yet, poll again
+    Pending,
+    /// Poll again after increasing the polling interval
+    SlowDown,
+}
+
+/// Reads the answer of the token endpoint when it isn't a token
+/// A server error is handled as `slow_down` since the provider is likely overloaded
+/// - Returns `Err(ZitadelCLIError::OAuth(status, error))` if the user denied the sign in, the device code expired
+///   or the body isn't an OAuth error document
+fn poll_outcome(status: StatusCode, body: &str) -> Result<PollOutcome, ZitadelCLIError> {
+    if status.is_server_error() {
+        info!("The token endpoint answered {status}, polling more slowly");
+        return Ok(PollOutcome::SlowDown);
+    }
+    let error_response = serde_json::from_str::<TokenErrorResponse>(body).map_err(|_| {
+        ZitadelCLIError::OAuth(
+            Some(status),
+            format!("Device authorization failed: {status} {body}"),
+        )
+    })?;
+    match error_response.error.as_str() {
+        "authorization_pending" => Ok(PollOutcome::Pending),
+        "slow_down" => Ok(PollOutcome::SlowDown),
+        _ => Err(ZitadelCLIError::OAuth(
+            Some(status),
+            format!(
+                "Device authorization failed: {} {}",
+                error_response.error,
+                error_response.error_description.unwrap_or_default(),
+            ),
+        )),
+    }
+}
+
+/// Initializes the flow configuration from the configuration, here is the full list of keys used:
+/// - `CONFIG_FILE_PATH` is the path of the file the access token is written to
+/// - `ISSUER` The url of the Zitadel instance
+/// - `CLIENT_ID` is the client id of the application
+/// - `SCOPES` is the scopes to request from the OpenID Connect provider
+///
+/// Returns the configuration of the application
+fn init_config(config: &Config) -> Result<DeviceCodeFlowAppConfig, ZitadelCLIError> {
+    Ok(DeviceCodeFlowAppConfig {
+        config_file_path: config.config_file_path()?,
+        issuer: config.issuer()?,
+        client_id: config.client_id()?,
+        scopes: config.scopes()?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_poll_outcome() {
+        let pending = r#"{"error":"authorization_pending"}"#;
+        assert_eq!(
+            poll_outcome(StatusCode::BAD_REQUEST, pending).unwrap(),
+            PollOutcome::Pending
+        );
+        let slow_down = r#"{"error":"slow_down"}"#;
+        assert_eq!(
+            poll_outcome(StatusCode::BAD_REQUEST, slow_down).unwrap(),
+            PollOutcome::SlowDown
+        );
+        assert_eq!(
+            poll_outcome(StatusCode::BAD_GATEWAY, "<html>Bad Gateway</html>").unwrap(),
+            PollOutcome::SlowDown
+        );
+
+        let denied = r#"{"error":"access_denied","error_description":"the user denied"}"#;
+        let error = poll_outcome(StatusCode::BAD_REQUEST, denied).unwrap_err();
+        assert!(matches!(
+            error,
+            ZitadelCLIError::OAuth(Some(StatusCode::BAD_REQUEST), message) if message.contains("access_denied")
+        ));
+        let error = poll_outcome(StatusCode::FORBIDDEN, "Forbidden").unwrap_err();
+        assert!(matches!(
+            error,
+            ZitadelCLIError::OAuth(Some(StatusCode::FORBIDDEN), message) if message.contains("Forbidden")
+        ));
+    }
+}
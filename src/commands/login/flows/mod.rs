@@ -1,54 +1,106 @@
-use std::path::PathBuf;
+use std::path::Path;
 
-use crate::error::ZitadelCLIError;
+use crate::{
+    commands::secret::write_secret,
+    config::{Config, ConfigKey},
+    error::ZitadelCLIError,
+};
 use authorization::login as authorization_login;
 use clap::ValueEnum;
 use client_credential::login as client_credential_login;
+use device_code::login as device_code_login;
+use jwt_profile::login as jwt_profile_login;
 use personal_access_token::login as personal_access_token_login;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use tracing::{error, info};
 
 pub(crate) mod authorization;
 pub(crate) mod client_credential;
+pub(crate) mod device_code;
+pub(crate) mod jwt_profile;
 pub(crate) mod personal_access_token;
 
-#[derive(Debug, Clone, ValueEnum)]
+#[derive(Debug, Clone, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
 pub enum Flow {
     AuthorizationCode,
     ClientCredentials,
     PersonalAccessToken,
+    DeviceCode,
+    JwtProfile,
 }
 
 impl Flow {
     /// Logs the user in using the specified flow
-    /// Writes the access token to the config file
-    pub async fn login(self, open_browser: bool) -> Result<(), ZitadelCLIError> {
+    /// Writes the access token to the config file, only readable by the current user
+    /// - Returns `Err(ZitadelCLIError::Config(errors))` listing every key of the flow missing from the configuration
+    pub async fn login(self, open_browser: bool, config: &Config) -> Result<(), ZitadelCLIError> {
+        config.validate(self.required_keys())?;
         match self {
-            Flow::AuthorizationCode => authorization_login(open_browser).await,
-            Flow::ClientCredentials => client_credential_login().await,
-            Flow::PersonalAccessToken => personal_access_token_login().await,
+            Flow::AuthorizationCode => authorization_login(open_browser, config).await,
+            Flow::ClientCredentials => client_credential_login(config).await,
+            Flow::PersonalAccessToken => personal_access_token_login(config).await,
+            Flow::DeviceCode => device_code_login(open_browser, config).await,
+            Flow::JwtProfile => jwt_profile_login(config).await,
+        }
+    }
+
+    /// Returns the configuration keys used by the flow
+    pub fn required_keys(&self) -> &'static [ConfigKey] {
+        match self {
+            Flow::AuthorizationCode => &[
+                ConfigKey::ConfigFilePath,
+                ConfigKey::Issuer,
+                ConfigKey::ClientId,
+                ConfigKey::CallbackServerAddress,
+                ConfigKey::Scopes,
+                ConfigKey::IsSecure,
+            ],
+            Flow::ClientCredentials => &[
+                ConfigKey::ConfigFilePath,
+                ConfigKey::Issuer,
+                ConfigKey::ClientId,
+                ConfigKey::ClientSecret,
+                ConfigKey::Scopes,
+            ],
+            Flow::PersonalAccessToken => {
+                &[ConfigKey::ConfigFilePath, ConfigKey::PersonalAccessToken]
+            }
+            Flow::DeviceCode => &[
+                ConfigKey::ConfigFilePath,
+                ConfigKey::Issuer,
+                ConfigKey::ClientId,
+                ConfigKey::Scopes,
+            ],
+            Flow::JwtProfile => &[
+                ConfigKey::ConfigFilePath,
+                ConfigKey::Issuer,
+                ConfigKey::KeyFilePath,
+                ConfigKey::Scopes,
+            ],
         }
     }
 }
 
-/// Writes the access token to the config file
+/// Writes the access token to the config file, only readable by the current user
 /// - `file_path`: The path to the config file
 /// - `access_token`: The access token to write to the config file
 /// - Returns `Ok(())` if the access token was written successfully
 /// - Returns `Err(ZitadelCLIError::IO(error))` if the access token was not written successfully
-pub fn save_config<T: Serialize>(
-    file_path: &PathBuf,
-    access_token: T,
-) -> Result<(), ZitadelCLIError> {
+pub fn save_config<T: Serialize>(file_path: &Path, access_token: T) -> Result<(), ZitadelCLIError> {
     info! {"Writing the access token to a file"};
-    match std::fs::write(file_path, serde_json::to_string(&access_token)?) {
+    if let Some(parent) = file_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let content = serde_json::to_string(&access_token)?;
+    match write_secret(file_path, content.as_bytes()) {
         Ok(_) => {
             info! {"Access token successfully written to file! You can now use other commands"};
             Ok(())
         }
         Err(error) => {
             error!("Error writing access token to file: {error}");
-            Err(ZitadelCLIError::IO(error))
+            Err(error)
         }
     }
 }
@@ -2,10 +2,7 @@ use std::path::PathBuf;
 
 use serde::Serialize;
 
-use crate::{
-    env::{config_file_path, personal_access_token},
-    error::ZitadelCLIError,
-};
+use crate::{commands::Token, config::Config, error::ZitadelCLIError};
 
 use super::save_config;
 
@@ -18,17 +15,23 @@ struct PersonalAccessTokenFlowAppConfig {
 
 /// Logs the user in using the personal access token flow
 /// Writes the personal access token to the config file
-pub(crate) async fn login() -> Result<(), ZitadelCLIError> {
-    let config: PersonalAccessTokenFlowAppConfig = init_config_from_env();
-    save_config(&config.config_file_path, config.personal_access_token)
+pub(crate) async fn login(config: &Config) -> Result<(), ZitadelCLIError> {
+    let config: PersonalAccessTokenFlowAppConfig = init_config(config)?;
+    let token = Token {
+        access_token: config.personal_access_token,
+        expires_in: None,
+        issued_at: None,
+        refresh_token: None,
+    };
+    save_config(&config.config_file_path, token)
 }
 
 /// Initializes the configuration for the personal access token flow
-fn init_config_from_env() -> PersonalAccessTokenFlowAppConfig {
-    let config_file_path = config_file_path();
-    let personal_access_token = personal_access_token();
-    PersonalAccessTokenFlowAppConfig {
+fn init_config(config: &Config) -> Result<PersonalAccessTokenFlowAppConfig, ZitadelCLIError> {
+    let config_file_path = config.config_file_path()?;
+    let personal_access_token = config.personal_access_token()?;
+    Ok(PersonalAccessTokenFlowAppConfig {
         config_file_path,
         personal_access_token,
-    }
+    })
 }
@@ -1,8 +1,15 @@
 pub(crate) mod flows;
 
-use serde::Deserialize;
+use std::time::{SystemTime, UNIX_EPOCH};
 
-use crate::{env::config_file_path, error::ZitadelCLIError};
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::{
+    client::http_client,
+    config::{Config, ConfigKey},
+    error::ZitadelCLIError,
+};
 
 /// NOTE: Some of the fields are missing right now and can be added later on as needed
 /// See [the openID Connect documentation](https://openid.net/specs/openid-connect-discovery-1_0.html#ProviderMetadata)
@@ -33,19 +40,99 @@ pub struct DiscoveryConfig {
     pub ui_locales_supported: Vec<String>,
     pub request_parameter_supported: bool,
     pub request_uri_parameter_supported: bool,
+    /// Only present if the provider supports the [Device Authorization Grant](https://www.rfc-editor.org/rfc/rfc8628)
+    pub device_authorization_endpoint: Option<String>,
 }
 
 /// Calls the `/.well-known/openid-configuration` of the provided url
 pub async fn discover(url: &str) -> Result<DiscoveryConfig, ZitadelCLIError> {
-    Ok(reqwest::get(url).await?.json::<DiscoveryConfig>().await?)
+    let response = http_client()?.get(url).send().await?;
+    Ok(response.json::<DiscoveryConfig>().await?)
 }
 
-#[derive(Deserialize)]
+/// Number of seconds before the actual expiry at which a token is already considered expired,
+/// so that it doesn't expire while a request is in flight
+const EXPIRY_LEEWAY_SECONDS: u64 = 30;
+
+#[derive(Deserialize, Serialize)]
 pub(crate) struct Token {
     pub access_token: String,
+    /// Lifetime of the token in seconds, missing for tokens that don't expire (e.g. personal access tokens)
+    #[serde(default)]
+    pub expires_in: Option<u64>,
+    /// Unix timestamp at which the token was issued
+    #[serde(default)]
+    pub issued_at: Option<u64>,
+    /// Only returned by the `Authorization Code Flow` when the `offline_access` scope is requested
+    #[serde(default)]
+    pub refresh_token: Option<String>,
 }
 
-pub(crate) fn load_access_token() -> Result<Token, ZitadelCLIError> {
-    let token_str = std::fs::read_to_string(config_file_path())?;
+impl Token {
+    /// Returns whether the token expired (or is about to), tokens without expiry informations never expire
+    pub fn is_expired(&self) -> bool {
+        match (self.issued_at, self.expires_in) {
+            (Some(issued_at), Some(expires_in)) => {
+                issued_at + expires_in <= unix_timestamp() + EXPIRY_LEEWAY_SECONDS
+            }
+            _ => false,
+        }
+    }
+}
+
+/// The configuration keys needed by every command interacting with the Zitadel API
+pub(crate) const API_KEYS: &[ConfigKey] = &[ConfigKey::ConfigFilePath, ConfigKey::Issuer];
+
+pub(crate) fn load_access_token(config: &Config) -> Result<Token, ZitadelCLIError> {
+    let config_file_path = config.config_file_path()?;
+    let token_str =
+        std::fs::read_to_string(&config_file_path).map_err(|error| match error.kind() {
+            std::io::ErrorKind::NotFound => ZitadelCLIError::NotLoggedIn(config_file_path),
+            _ => error.into(),
+        })?;
     Ok(serde_json::from_str(&token_str)?)
 }
+
+/// Loads the access token and renews it beforehand if it expired and a refresh token is available
+/// If it expired without a refresh token, it is returned as is and the API will reject it
+pub(crate) async fn load_valid_access_token(config: &Config) -> Result<Token, ZitadelCLIError> {
+    let token = load_access_token(config)?;
+    match (token.is_expired(), &token.refresh_token) {
+        (true, Some(refresh_token)) => {
+            info! {"The access token expired, refreshing it"};
+            flows::authorization::refresh(config, refresh_token).await
+        }
+        _ => Ok(token),
+    }
+}
+
+/// Returns the current time as a unix timestamp in seconds
+pub(crate) fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token(issued_at: Option<u64>, expires_in: Option<u64>) -> Token {
+        Token {
+            access_token: "access_token".to_string(),
+            expires_in,
+            issued_at,
+            refresh_token: None,
+        }
+    }
+
+    #[test]
+    fn test_token_is_expired() {
+        let now = unix_timestamp();
+        assert!(!token(Some(now - 3600), Some(43200)).is_expired());
+        assert!(token(Some(now - 43200), Some(43200)).is_expired());
+        assert!(token(Some(now), Some(10)).is_expired());
+        assert!(!token(None, None).is_expired());
+    }
+}
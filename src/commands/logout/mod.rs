@@ -1,10 +1,10 @@
 use std::fs;
 
-use crate::env::config_file_path;
+use crate::{config::Config, error::ZitadelCLIError};
 
-/// Logs out the user by deleting the file specified in the `CONFIG_FILE_PATH` environment variable
-pub(crate) fn logout() -> std::io::Result<()> {
-    let config_file = config_file_path();
+/// Logs out the user by deleting the file specified by `CONFIG_FILE_PATH`
+pub(crate) fn logout(config: &Config) -> Result<(), ZitadelCLIError> {
+    let config_file = config.config_file_path()?;
     fs::remove_file(config_file)?;
     Ok(())
 }
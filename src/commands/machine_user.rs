@@ -0,0 +1,109 @@
+//! Credentials of the machine users, the secrets returned by these endpoints are only shown once
+//! so they are either printed or written to a file
+
+use std::path::Path;
+
+use base64::{engine::general_purpose, Engine};
+use reqwest::Method;
+use serde::Deserialize;
+use serde_json::json;
+use tracing::info;
+
+use crate::{client::ZitadelClient, config::Config, error::ZitadelCLIError};
+
+use super::{
+    request::send_request,
+    secret::{output_secret, write_secret},
+};
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AddKeyResponse {
+    key_id: String,
+    /// The base64 encoded JSON key file
+    key_details: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GenerateSecretResponse {
+    client_id: String,
+    client_secret: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AddPersonalAccessTokenResponse {
+    token_id: String,
+    token: String,
+}
+
+/// Adds a JSON key to the machine user and writes the key file to `output_file`,
+/// the key file can then be used to log in with the `JWT Profile Flow`
+/// - `expiration_date`: The expiration date of the key in the RFC 3339 format, the key never expires if missing
+pub(crate) async fn handle_add_key(
+    config: &Config,
+    client: &ZitadelClient,
+    user_id: &str,
+    expiration_date: Option<&str>,
+    output_file: &Path,
+) -> Result<(), ZitadelCLIError> {
+    let body = json!({ "type": "KEY_TYPE_JSON", "expirationDate": expiration_date });
+    let endpoint = format!("/management/v1/users/{user_id}/keys");
+    let response = send_request(client, Method::POST, &endpoint, Some(body)).await?;
+    let response: AddKeyResponse =
+        serde_json::from_value(response).map_err(ZitadelCLIError::InvalidResponse)?;
+    let key_file = general_purpose::STANDARD.decode(response.key_details)?;
+    write_secret(output_file, &key_file)?;
+    info!(
+        "Key {} added successfully and written to {}",
+        response.key_id,
+        output_file.display()
+    );
+    config.output.print(&json!({ "keyId": response.key_id }))
+}
+
+/// Generates a client secret for the machine user, needed by the `Client Credential Flow`
+/// The client id and secret are written to `output_file` if specified, printed otherwise
+pub(crate) async fn handle_generate_secret(
+    config: &Config,
+    client: &ZitadelClient,
+    user_id: &str,
+    output_file: Option<&Path>,
+) -> Result<(), ZitadelCLIError> {
+    let endpoint = format!("/management/v1/users/{user_id}/secret");
+    let response = send_request(client, Method::PUT, &endpoint, Some(json!({}))).await?;
+    let response: GenerateSecretResponse =
+        serde_json::from_value(response).map_err(ZitadelCLIError::InvalidResponse)?;
+    output_secret(
+        &config.output,
+        output_file,
+        &json!({ "clientId": response.client_id, "clientSecret": response.client_secret }),
+    )?;
+    info!("Client secret generated successfully");
+    Ok(())
+}
+
+/// Adds a personal access token to the machine user, needed by the `Personal Access Token Flow`
+/// The token is written to `output_file` if specified, printed otherwise
+/// - `expiration_date`: The expiration date of the token in the RFC 3339 format, the token never expires if missing
+pub(crate) async fn handle_add_personal_access_token(
+    config: &Config,
+    client: &ZitadelClient,
+    user_id: &str,
+    expiration_date: Option<&str>,
+    output_file: Option<&Path>,
+) -> Result<(), ZitadelCLIError> {
+    let endpoint = format!("/management/v1/users/{user_id}/pats");
+    let body = json!({ "expirationDate": expiration_date });
+    let response = send_request(client, Method::POST, &endpoint, Some(body)).await?;
+    let response: AddPersonalAccessTokenResponse =
+        serde_json::from_value(response).map_err(ZitadelCLIError::InvalidResponse)?;
+    output_secret(
+        &config.output,
+        output_file,
+        &json!({ "tokenId": response.token_id, "token": response.token }),
+    )?;
+    info!("Personal access token added successfully");
+    Ok(())
+}
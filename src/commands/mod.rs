@@ -1,6 +1,15 @@
 pub(crate) mod add;
+pub(crate) mod application;
+pub(crate) mod apply;
+pub(crate) mod context;
+pub(crate) mod export;
+pub(crate) mod import;
 pub(crate) mod login;
 pub(crate) mod logout;
+pub(crate) mod machine_user;
+pub(crate) mod org;
+pub(crate) mod request;
+pub(crate) mod secret;
 
 pub(crate) use login::*;
 pub(crate) use logout::*;
@@ -0,0 +1,170 @@
+//! Resolution of the organization targeted with `--org`, sent to the API as the `x-zitadel-orgid` header
+
+use base64::{engine::general_purpose, Engine};
+use clap::ValueEnum;
+use reqwest::Method;
+use serde::Deserialize;
+use serde_json::json;
+use tracing::info;
+
+use crate::{client::ZitadelClient, config::Config, error::ZitadelCLIError};
+
+use super::request::send_request;
+
+/// The header used by Zitadel to select the organization of a request
+pub(crate) const ORG_ID_HEADER: &str = "x-zitadel-orgid";
+
+#[derive(Deserialize)]
+struct OrganizationSearchResponse {
+    #[serde(default)]
+    result: Vec<OrganizationSearchResult>,
+}
+
+#[derive(Deserialize)]
+struct OrganizationSearchResult {
+    id: String,
+}
+
+#[derive(Clone, ValueEnum)]
+/// The ways to prove the ownership of a domain
+pub enum DomainValidationType {
+    /// A TXT record on the domain
+    Dns,
+    /// A file served by the domain
+    Http,
+}
+
+impl DomainValidationType {
+    /// Returns the name of the validation type in the Zitadel API
+    fn as_api_str(&self) -> &'static str {
+        match self {
+            DomainValidationType::Dns => "DOMAIN_VALIDATION_TYPE_DNS",
+            DomainValidationType::Http => "DOMAIN_VALIDATION_TYPE_HTTP",
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct ValidationTokenResponse {
+    token: String,
+    url: String,
+}
+
+#[derive(Deserialize)]
+struct GetMetadataResponse {
+    metadata: Metadata,
+}
+
+#[derive(Deserialize)]
+struct Metadata {
+    /// The base64 encoded value
+    value: String,
+}
+
+/// Returns whether `org` is already an organization id, Zitadel ids are made of digits only
+fn is_org_id(org: &str) -> bool {
+    !org.is_empty() && org.chars().all(|character| character.is_ascii_digit())
+}
+
+/// Returns the id of the organization `org`
+/// - `client`: A client that doesn't target any organization yet
+/// - `org`: The id or the exact name of the organization
+/// - Returns `Err(ZitadelCLIError::Organization(error))` if no organization or several organizations have this name
+pub(crate) async fn resolve_org_id(
+    client: &ZitadelClient,
+    org: &str,
+) -> Result<String, ZitadelCLIError> {
+    find_org_id(client, org)
+        .await?
+        .ok_or_else(|| ZitadelCLIError::Organization(format!("No organization named {org}")))
+}
+
+/// Returns the id of the organization `org`, `None` if no organization has this name
+/// - Returns `Err(ZitadelCLIError::Organization(error))` if several organizations have this name
+pub(crate) async fn find_org_id(
+    client: &ZitadelClient,
+    org: &str,
+) -> Result<Option<String>, ZitadelCLIError> {
+    if is_org_id(org) {
+        return Ok(Some(org.to_string()));
+    }
+    let body = json!({
+        "queries": [{
+            "nameQuery": {
+                "name": org,
+                "method": "TEXT_QUERY_METHOD_EQUALS"
+            }
+        }]
+    });
+    let response = client
+        .request(Method::POST, "/v2/organizations/_search", Some(&body))
+        .await?;
+    let mut organizations = serde_json::from_value::<OrganizationSearchResponse>(response)
+        .map_err(ZitadelCLIError::InvalidResponse)?
+        .result;
+    match organizations.len() {
+        0 => Ok(None),
+        1 => Ok(Some(organizations.remove(0).id)),
+        _ => Err(ZitadelCLIError::Organization(format!(
+            "Several organizations are named {org}, use its id instead"
+        ))),
+    }
+}
+
+/// Generates a validation token for a domain of the organization and prints how to publish it,
+/// the domain is then verified with `org domain verify`
+/// - `add`: Whether the domain is added to the organization before generating the token
+pub(crate) async fn handle_domain_validation_token(
+    config: &Config,
+    client: &ZitadelClient,
+    domain: &str,
+    validation_type: &DomainValidationType,
+    add: bool,
+) -> Result<(), ZitadelCLIError> {
+    if add {
+        let body = json!({ "domain": domain });
+        let endpoint = "/management/v1/orgs/me/domains";
+        send_request(client, Method::POST, endpoint, Some(body)).await?;
+        info!("Domain {domain} added successfully");
+    }
+    let endpoint = format!("/management/v1/orgs/me/domains/{domain}/validation/_generate");
+    let body = json!({ "type": validation_type.as_api_str() });
+    let response = send_request(client, Method::POST, &endpoint, Some(body)).await?;
+    let ValidationTokenResponse { token, url } =
+        serde_json::from_value(response).map_err(ZitadelCLIError::InvalidResponse)?;
+    match validation_type {
+        DomainValidationType::Dns => info!("Create a TXT record {url} with the value {token}"),
+        DomainValidationType::Http => info!("Serve a file containing {token} at {url}"),
+    }
+    config
+        .output
+        .print(&json!({ "domain": domain, "token": token, "url": url }))
+}
+
+/// Prints the key and the decoded value of a metadata of the organization
+pub(crate) async fn handle_get_metadata(
+    config: &Config,
+    client: &ZitadelClient,
+    key: &str,
+) -> Result<(), ZitadelCLIError> {
+    let endpoint = format!("/management/v1/metadata/{key}");
+    let response = send_request(client, Method::GET, &endpoint, None).await?;
+    let response: GetMetadataResponse =
+        serde_json::from_value(response).map_err(ZitadelCLIError::InvalidResponse)?;
+    let value = general_purpose::STANDARD.decode(response.metadata.value)?;
+    let value = String::from_utf8_lossy(&value);
+    config.output.print(&json!({ "key": key, "value": value }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_org_id() {
+        assert!(is_org_id("259242039378444290"));
+        assert!(!is_org_id("ACME"));
+        assert!(!is_org_id("acme-2"));
+        assert!(!is_org_id(""));
+    }
+}
@@ -0,0 +1,85 @@
+//! Generic authenticated requests to the Zitadel API, used by every resource command
+//! Each command creates a single [`ZitadelClient`], shared by all its requests, so the access token is loaded and
+//! the organization resolved once
+
+use reqwest::Method;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{json, Value};
+use std::path::Path;
+use tracing::info;
+
+use crate::{
+    client::ZitadelClient, config::Config, error::ZitadelCLIError, output::OutputFormat,
+    payloads::load_payload,
+};
+
+/// Generic function to send a request to the Zitadel instance and print the response in the output format
+/// - `method`: The HTTP method of the endpoint
+/// - `endpoint`: The endpoint, relative to the issuer
+/// - `body`: The JSON body of the request, if any
+pub(crate) async fn handle_request(
+    config: &Config,
+    client: &ZitadelClient,
+    method: Method,
+    endpoint: &str,
+    body: Option<Value>,
+) -> Result<(), ZitadelCLIError> {
+    let response = send_request(client, method.clone(), endpoint, body).await?;
+    info!("{method} {endpoint} succeeded");
+    config.output.print(&response)
+}
+
+/// Generic function to list the resources of a search endpoint and print them in the output format
+/// The pages are fetched as selected by `--page-size`, `--limit`, `--sort` and `--all`
+/// - `endpoint`: The search endpoint, relative to the issuer
+/// - `body`: The search body, its `query` is set for each page
+pub(crate) async fn handle_list(
+    config: &Config,
+    client: &ZitadelClient,
+    endpoint: &str,
+    body: Value,
+) -> Result<(), ZitadelCLIError> {
+    let mut result = vec![];
+    let total = client
+        .paginate(endpoint, body, &config.pagination, |items| {
+            match config.output.format {
+                OutputFormat::Ndjson => config.output.print(&Value::Array(items))?,
+                _ => result.extend(items),
+            }
+            Ok(())
+        })
+        .await?;
+    info!("POST {endpoint} succeeded, {total} resources match the search");
+    if config.output.format == OutputFormat::Ndjson {
+        return Ok(());
+    }
+    let response = json!({ "details": { "totalResult": total.to_string() }, "result": result });
+    config.output.print(&response)
+}
+
+/// Generic function to update an entity of the Zitadel instance with the payload stored in `file_path`, `-` for the
+/// standard input
+pub(crate) async fn handle_update_entity<T>(
+    config: &Config,
+    client: &ZitadelClient,
+    method: Method,
+    file_path: &Path,
+    endpoint: &str,
+) -> Result<(), ZitadelCLIError>
+where
+    T: DeserializeOwned + Serialize,
+{
+    let body = serde_json::to_value(load_payload::<T>(file_path, config.payload_format)?)?;
+    handle_request(config, client, method, endpoint, Some(body)).await
+}
+
+/// Sends the request with the client of the command and returns the parsed JSON response
+/// An empty response body is returned as an empty JSON object
+pub(crate) async fn send_request(
+    client: &ZitadelClient,
+    method: Method,
+    endpoint: &str,
+    body: Option<Value>,
+) -> Result<Value, ZitadelCLIError> {
+    client.request(method, endpoint, body.as_ref()).await
+}
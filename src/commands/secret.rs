@@ -0,0 +1,40 @@
+//! Output of the secrets returned by the Zitadel API, which are only shown once
+
+use std::path::Path;
+
+use serde_json::Value;
+use tracing::info;
+
+use crate::{error::ZitadelCLIError, output::Output};
+
+/// Writes `secret` to `output_file` as JSON if specified, prints it in the `output` format otherwise
+/// `--fields` is ignored and `--output id` prints JSON, so the secret is never dropped
+pub(crate) fn output_secret(
+    output: &Output,
+    output_file: Option<&Path>,
+    secret: &Value,
+) -> Result<(), ZitadelCLIError> {
+    match output_file {
+        Some(output_file) => {
+            let secret = serde_json::to_string_pretty(secret)?;
+            write_secret(output_file, secret.as_bytes())?;
+            info!("Secret written to {}", output_file.display());
+        }
+        None => output.unfiltered().print(secret)?,
+    }
+    Ok(())
+}
+
+/// Writes a secret to a file only readable by the current user, the permissions of an existing file are restricted
+/// before it is written
+pub(crate) fn write_secret(path: &Path, content: &[u8]) -> Result<(), ZitadelCLIError> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options.open(path)?;
+    #[cfg(unix)]
+    file.set_permissions(std::os::unix::fs::PermissionsExt::from_mode(0o600))?;
+    std::io::Write::write_all(&mut file, content)?;
+    Ok(())
+}
@@ -0,0 +1,481 @@
+//! This module contains the configuration of the zitadel CLI
+//! The configuration is built from several layers, each one overriding the previous ones:
+//! 1. The defaults
+//! 2. The context, see [`crate::context`]
+//! 3. The environment variables, see [`crate::env`]
+//! 4. The CLI flags
+//!
+//! A context passed explicitly with `--context` outranks the environment variables for the keys identifying the
+//! instance and the credentials, see [`IDENTITY_KEYS`], so a leftover `ISSUER` doesn't mix two instances
+//!
+//! The values are only checked once the command knows which keys it needs, so every missing or malformed key
+//! is reported at once instead of failing on the first one
+
+use std::{collections::BTreeMap, path::PathBuf};
+
+use tracing::warn;
+
+use crate::{
+    client::Pagination, commands::flows::Flow, context::Context, env, error::ZitadelCLIError,
+    output::Output, payloads::PayloadFormat,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+/// The keys of the configuration
+pub(crate) enum ConfigKey {
+    ConfigFilePath,
+    Issuer,
+    ClientId,
+    ClientSecret,
+    CallbackServerAddress,
+    Scopes,
+    IsSecure,
+    PersonalAccessToken,
+    KeyFilePath,
+    Flow,
+    Org,
+}
+
+/// The keys identifying the instance and the credentials of a context
+const IDENTITY_KEYS: [ConfigKey; 4] = [
+    ConfigKey::ConfigFilePath,
+    ConfigKey::Issuer,
+    ConfigKey::ClientId,
+    ConfigKey::KeyFilePath,
+];
+
+impl ConfigKey {
+    /// Returns the name of the environment variable of the key
+    pub(crate) fn env_name(&self) -> &'static str {
+        match self {
+            ConfigKey::ConfigFilePath => "CONFIG_FILE_PATH",
+            ConfigKey::Issuer => "ISSUER",
+            ConfigKey::ClientId => "CLIENT_ID",
+            ConfigKey::ClientSecret => "CLIENT_SECRET",
+            ConfigKey::CallbackServerAddress => "CALLBACK_SERVER_ADDRESS",
+            ConfigKey::Scopes => "SCOPES",
+            ConfigKey::IsSecure => "IS_SECURE",
+            ConfigKey::PersonalAccessToken => "PERSONAL_ACCESS_TOKEN",
+            ConfigKey::KeyFilePath => "KEY_FILE_PATH",
+            ConfigKey::Flow => "FLOW",
+            ConfigKey::Org => "ORG",
+        }
+    }
+
+    /// Returns the CLI flag overriding the key, secrets can't be passed as flags so they don't end up in the shell history
+    fn flag(&self) -> Option<&'static str> {
+        match self {
+            ConfigKey::ConfigFilePath => Some("--config-file-path"),
+            ConfigKey::Issuer => Some("--issuer"),
+            ConfigKey::ClientId => Some("--client-id"),
+            ConfigKey::CallbackServerAddress => Some("--callback-server-address"),
+            ConfigKey::Scopes => Some("--scopes"),
+            ConfigKey::KeyFilePath => Some("--key-file-path"),
+            ConfigKey::Flow => Some("--flow"),
+            ConfigKey::Org => Some("--org"),
+            ConfigKey::ClientSecret | ConfigKey::IsSecure | ConfigKey::PersonalAccessToken => None,
+        }
+    }
+
+    /// Describes where the key can be set
+    fn sources(&self) -> String {
+        match self.flag() {
+            Some(flag) => format!(
+                "the context, the `{}` environment variable or `{flag}`",
+                self.env_name()
+            ),
+            None => format!(
+                "the context or the `{}` environment variable",
+                self.env_name()
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+/// The configuration of the zitadel CLI, also used to represent a single layer before they are merged
+pub(crate) struct Config {
+    pub(crate) config_file_path: Option<PathBuf>,
+    pub(crate) issuer: Option<String>,
+    pub(crate) client_id: Option<String>,
+    pub(crate) client_secret: Option<String>,
+    pub(crate) callback_server_address: Option<String>,
+    pub(crate) scopes: Option<String>,
+    pub(crate) is_secure: Option<bool>,
+    pub(crate) personal_access_token: Option<String>,
+    pub(crate) key_file_path: Option<PathBuf>,
+    pub(crate) flow: Option<Flow>,
+    /// The id or name of the organization targeted by the resource commands, the default organization is used if missing
+    pub(crate) org: Option<String>,
+    /// The keys whose value couldn't be parsed, with the reason
+    pub(crate) malformed: BTreeMap<ConfigKey, String>,
+    /// How the responses are printed, only set by the CLI flags
+    pub(crate) output: Output,
+    /// Which pages of the list commands are fetched, only set by the CLI flags
+    pub(crate) pagination: Pagination,
+    /// The format of the payloads, detected from the extension of their file if missing, only set by the CLI flags
+    pub(crate) payload_format: Option<PayloadFormat>,
+}
+
+impl From<Context> for Config {
+    fn from(context: Context) -> Self {
+        Config {
+            config_file_path: Some(context.credentials_file_path),
+            issuer: Some(context.issuer),
+            client_id: context.client_id,
+            client_secret: context.client_secret,
+            callback_server_address: context.callback_server_address,
+            scopes: context.scopes,
+            is_secure: context.is_secure,
+            personal_access_token: context.personal_access_token,
+            key_file_path: context.key_file_path,
+            flow: context.flow,
+            org: context.org,
+            ..Default::default()
+        }
+    }
+}
+
+/// Replaces `lower` by `higher` if it is set, in which case a previous parsing error of the key doesn't matter anymore
+fn overlay<T>(
+    lower: &mut Option<T>,
+    higher: Option<T>,
+    key: ConfigKey,
+    malformed: &mut BTreeMap<ConfigKey, String>,
+) {
+    if higher.is_some() {
+        *lower = higher;
+        malformed.remove(&key);
+    }
+}
+
+/// Returns the value of a required key
+fn required<T: Clone>(value: &Option<T>, key: ConfigKey) -> Result<T, ZitadelCLIError> {
+    value
+        .clone()
+        .ok_or_else(|| ZitadelCLIError::Config(vec![missing_message(key)]))
+}
+
+fn missing_message(key: ConfigKey) -> String {
+    format!("{} is missing, set it in {}", key.env_name(), key.sources())
+}
+
+impl Config {
+    /// Builds the configuration from the defaults, the context, the environment variables and the CLI flags
+    /// - `context`: The context selected for the invocation, if any
+    /// - `explicit_context`: Whether the context was passed with `--context`, rather than being the current one
+    /// - `flags`: The configuration passed as CLI flags
+    pub(crate) fn load(context: Option<Context>, explicit_context: bool, flags: Config) -> Config {
+        Config::from_layers(
+            context.map(Config::from),
+            explicit_context,
+            env::config(),
+            flags,
+        )
+    }
+
+    /// Merges the layers of [`Config::load`]
+    /// - The environment variables setting an [`IDENTITY_KEYS`] of the context to another value are ignored if
+    ///   the context is explicit, and override the current context with a warning otherwise
+    fn from_layers(
+        context: Option<Config>,
+        explicit_context: bool,
+        mut env: Config,
+        flags: Config,
+    ) -> Config {
+        let mut config = Config::defaults();
+        if let Some(context) = context {
+            let conflicts: Vec<ConfigKey> = IDENTITY_KEYS
+                .into_iter()
+                .filter(|key| context.conflicts_with(&env, *key))
+                .collect();
+            if !conflicts.is_empty() {
+                let names = conflicts
+                    .iter()
+                    .map(ConfigKey::env_name)
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                match explicit_context {
+                    true => {
+                        warn!("{names} ignored, the context passed with --context takes precedence");
+                        conflicts.into_iter().for_each(|key| env.clear(key));
+                    }
+                    false => warn!(
+                        "{names} override the current context, pass --context to use the context instead"
+                    ),
+                }
+            }
+            config = config.merge(context);
+        }
+        let output = flags.output.clone();
+        let pagination = flags.pagination.clone();
+        let payload_format = flags.payload_format;
+        Config {
+            output,
+            pagination,
+            payload_format,
+            ..config.merge(env).merge(flags)
+        }
+    }
+
+    fn defaults() -> Config {
+        Config {
+            is_secure: Some(false),
+            flow: Some(Flow::ClientCredentials),
+            ..Default::default()
+        }
+    }
+
+    /// Whether `key` is set in both layers to different values, only for the [`IDENTITY_KEYS`]
+    fn conflicts_with(&self, other: &Config, key: ConfigKey) -> bool {
+        fn differ<T: PartialEq>(lower: &Option<T>, higher: &Option<T>) -> bool {
+            lower.is_some() && higher.is_some() && lower != higher
+        }
+        match key {
+            ConfigKey::ConfigFilePath => differ(&self.config_file_path, &other.config_file_path),
+            ConfigKey::Issuer => differ(&self.issuer, &other.issuer),
+            ConfigKey::ClientId => differ(&self.client_id, &other.client_id),
+            ConfigKey::KeyFilePath => differ(&self.key_file_path, &other.key_file_path),
+            _ => false,
+        }
+    }
+
+    /// Unsets one of the [`IDENTITY_KEYS`]
+    fn clear(&mut self, key: ConfigKey) {
+        match key {
+            ConfigKey::ConfigFilePath => self.config_file_path = None,
+            ConfigKey::Issuer => self.issuer = None,
+            ConfigKey::ClientId => self.client_id = None,
+            ConfigKey::KeyFilePath => self.key_file_path = None,
+            _ => {}
+        }
+    }
+
+    /// Merges `higher` on top of the configuration, the values set in `higher` take precedence
+    pub(crate) fn merge(mut self, higher: Config) -> Config {
+        let malformed = &mut self.malformed;
+        overlay(
+            &mut self.config_file_path,
+            higher.config_file_path,
+            ConfigKey::ConfigFilePath,
+            malformed,
+        );
+        overlay(
+            &mut self.issuer,
+            higher.issuer,
+            ConfigKey::Issuer,
+            malformed,
+        );
+        overlay(
+            &mut self.client_id,
+            higher.client_id,
+            ConfigKey::ClientId,
+            malformed,
+        );
+        overlay(
+            &mut self.client_secret,
+            higher.client_secret,
+            ConfigKey::ClientSecret,
+            malformed,
+        );
+        overlay(
+            &mut self.callback_server_address,
+            higher.callback_server_address,
+            ConfigKey::CallbackServerAddress,
+            malformed,
+        );
+        overlay(
+            &mut self.scopes,
+            higher.scopes,
+            ConfigKey::Scopes,
+            malformed,
+        );
+        overlay(
+            &mut self.is_secure,
+            higher.is_secure,
+            ConfigKey::IsSecure,
+            malformed,
+        );
+        overlay(
+            &mut self.personal_access_token,
+            higher.personal_access_token,
+            ConfigKey::PersonalAccessToken,
+            malformed,
+        );
+        overlay(
+            &mut self.key_file_path,
+            higher.key_file_path,
+            ConfigKey::KeyFilePath,
+            malformed,
+        );
+        overlay(&mut self.flow, higher.flow, ConfigKey::Flow, malformed);
+        overlay(&mut self.org, higher.org, ConfigKey::Org, malformed);
+        self.malformed.extend(higher.malformed);
+        self
+    }
+
+    /// Checks that all the `keys` are set and well formed
+    /// - Returns `Err(ZitadelCLIError::Config(errors))` listing every missing or malformed key
+    pub(crate) fn validate(&self, keys: &[ConfigKey]) -> Result<(), ZitadelCLIError> {
+        let errors: Vec<String> = keys
+            .iter()
+            .filter_map(|key| match self.malformed.get(key) {
+                Some(reason) => Some(format!("{} is malformed: {reason}", key.env_name())),
+                None if self.is_missing(*key) => Some(missing_message(*key)),
+                None => None,
+            })
+            .chain(
+                self.issuer
+                    .as_ref()
+                    .filter(|_| keys.contains(&ConfigKey::Issuer))
+                    .and_then(|issuer| url::Url::parse(issuer).err())
+                    .map(|error| format!("ISSUER is malformed: {error}")),
+            )
+            .collect();
+        match errors.is_empty() {
+            true => Ok(()),
+            false => Err(ZitadelCLIError::Config(errors)),
+        }
+    }
+
+    fn is_missing(&self, key: ConfigKey) -> bool {
+        match key {
+            ConfigKey::ConfigFilePath => self.config_file_path.is_none(),
+            ConfigKey::Issuer => self.issuer.is_none(),
+            ConfigKey::ClientId => self.client_id.is_none(),
+            ConfigKey::ClientSecret => self.client_secret.is_none(),
+            ConfigKey::CallbackServerAddress => self.callback_server_address.is_none(),
+            ConfigKey::Scopes => self.scopes.is_none(),
+            ConfigKey::IsSecure => self.is_secure.is_none(),
+            ConfigKey::PersonalAccessToken => self.personal_access_token.is_none(),
+            ConfigKey::KeyFilePath => self.key_file_path.is_none(),
+            ConfigKey::Flow => self.flow.is_none(),
+            ConfigKey::Org => self.org.is_none(),
+        }
+    }
+
+    /// Returns the path to the file the access token is stored in
+    pub(crate) fn config_file_path(&self) -> Result<PathBuf, ZitadelCLIError> {
+        required(&self.config_file_path, ConfigKey::ConfigFilePath)
+    }
+
+    /// Returns the url of the Zitadel instance
+    pub(crate) fn issuer(&self) -> Result<String, ZitadelCLIError> {
+        required(&self.issuer, ConfigKey::Issuer)
+    }
+
+    pub(crate) fn client_id(&self) -> Result<String, ZitadelCLIError> {
+        required(&self.client_id, ConfigKey::ClientId)
+    }
+
+    pub(crate) fn client_secret(&self) -> Result<String, ZitadelCLIError> {
+        required(&self.client_secret, ConfigKey::ClientSecret)
+    }
+
+    pub(crate) fn callback_server_address(&self) -> Result<String, ZitadelCLIError> {
+        required(
+            &self.callback_server_address,
+            ConfigKey::CallbackServerAddress,
+        )
+    }
+
+    pub(crate) fn scopes(&self) -> Result<String, ZitadelCLIError> {
+        required(&self.scopes, ConfigKey::Scopes)
+    }
+
+    /// Returns whether the callback server is secure or not (https or http)
+    pub(crate) fn is_secure(&self) -> bool {
+        self.is_secure.unwrap_or(false)
+    }
+
+    pub(crate) fn personal_access_token(&self) -> Result<String, ZitadelCLIError> {
+        required(&self.personal_access_token, ConfigKey::PersonalAccessToken)
+    }
+
+    pub(crate) fn key_file_path(&self) -> Result<PathBuf, ZitadelCLIError> {
+        required(&self.key_file_path, ConfigKey::KeyFilePath)
+    }
+
+    /// Returns the flow to log in with
+    pub(crate) fn flow(&self) -> Flow {
+        self.flow.clone().unwrap_or(Flow::ClientCredentials)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_precedence() {
+        let lower = Config {
+            issuer: Some("https://lower.com".to_string()),
+            scopes: Some("openid".to_string()),
+            malformed: BTreeMap::from([(ConfigKey::IsSecure, "invalid bool".to_string())]),
+            ..Default::default()
+        };
+        let higher = Config {
+            issuer: Some("https://higher.com".to_string()),
+            is_secure: Some(true),
+            ..Default::default()
+        };
+        let config = lower.merge(higher);
+        assert_eq!(config.issuer.as_deref(), Some("https://higher.com"));
+        assert_eq!(config.scopes.as_deref(), Some("openid"));
+        assert_eq!(config.is_secure, Some(true));
+        assert!(config.malformed.is_empty());
+    }
+
+    #[test]
+    fn test_explicit_context_precedence() {
+        let context = Config {
+            issuer: Some("https://context.com".to_string()),
+            client_id: Some("context".to_string()),
+            ..Default::default()
+        };
+        let env = Config {
+            issuer: Some("https://env.com".to_string()),
+            scopes: Some("openid".to_string()),
+            ..Default::default()
+        };
+        let flags = Config {
+            client_id: Some("flag".to_string()),
+            ..Default::default()
+        };
+
+        let config = Config::from_layers(Some(context.clone()), true, env.clone(), flags.clone());
+        assert_eq!(config.issuer.as_deref(), Some("https://context.com"));
+        assert_eq!(config.scopes.as_deref(), Some("openid"));
+        assert_eq!(config.client_id.as_deref(), Some("flag"));
+
+        let config = Config::from_layers(Some(context), false, env, flags);
+        assert_eq!(config.issuer.as_deref(), Some("https://env.com"));
+    }
+
+    #[test]
+    fn test_validate_lists_every_error() {
+        let config = Config {
+            issuer: Some("not a url".to_string()),
+            malformed: BTreeMap::from([(ConfigKey::Flow, "unknown flow".to_string())]),
+            ..Default::default()
+        };
+        let error = config
+            .validate(&[
+                ConfigKey::ConfigFilePath,
+                ConfigKey::Issuer,
+                ConfigKey::ClientId,
+                ConfigKey::Flow,
+            ])
+            .unwrap_err();
+        match error {
+            ZitadelCLIError::Config(errors) => {
+                assert_eq!(errors.len(), 4);
+                assert!(errors[0].starts_with("CONFIG_FILE_PATH is missing"));
+                assert!(errors[1].starts_with("CLIENT_ID is missing"));
+                assert_eq!(errors[2], "FLOW is malformed: unknown flow");
+                assert!(errors[3].starts_with("ISSUER is malformed"));
+            }
+            error => panic!("Unexpected error {error}"),
+        }
+    }
+}
@@ -0,0 +1,172 @@
+//! This module contains the named contexts of the zitadel CLI
+//! A context holds everything needed to log in to a Zitadel instance, so switching between instances
+//! (e.g. dev, staging and prod) doesn't require different environment variables
+//! The contexts are stored in `$XDG_CONFIG_HOME/zitadel-cli/config.toml`, defaulting to `~/.config/zitadel-cli/config.toml`
+
+use std::{
+    collections::BTreeMap,
+    path::{Component, Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    commands::{flows::Flow, secret::write_secret},
+    error::ZitadelCLIError,
+};
+
+#[derive(Serialize, Deserialize, Default, Debug)]
+/// The file holding all the contexts
+pub(crate) struct ContextStore {
+    /// The context used when `--context` isn't specified
+    pub(crate) current_context: Option<String>,
+    #[serde(default)]
+    pub(crate) contexts: BTreeMap<String, Context>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+/// A Zitadel instance and the way to log in to it
+/// It is one of the layers of the [`crate::config::Config`], so the environment variables and CLI flags take precedence
+pub(crate) struct Context {
+    pub(crate) issuer: String,
+    pub(crate) client_id: Option<String>,
+    pub(crate) client_secret: Option<String>,
+    pub(crate) flow: Option<Flow>,
+    pub(crate) scopes: Option<String>,
+    pub(crate) callback_server_address: Option<String>,
+    pub(crate) is_secure: Option<bool>,
+    pub(crate) personal_access_token: Option<String>,
+    pub(crate) key_file_path: Option<PathBuf>,
+    /// The id or name of the organization targeted by default
+    pub(crate) org: Option<String>,
+    /// The file the credentials of this context are cached in
+    pub(crate) credentials_file_path: PathBuf,
+}
+
+impl ContextStore {
+    /// Loads the store, an empty store is returned if the file doesn't exist yet
+    pub(crate) fn load() -> Result<Self, ZitadelCLIError> {
+        match std::fs::read_to_string(store_file_path()?) {
+            Ok(content) => Ok(toml::from_str(&content)?),
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    /// Writes the store, creating the configuration directory if needed
+    /// The file is only readable by the current user since the contexts may hold client secrets and tokens
+    pub(crate) fn save(&self) -> Result<(), ZitadelCLIError> {
+        std::fs::create_dir_all(config_dir()?)?;
+        let content = toml::to_string_pretty(self)?;
+        write_secret(&store_file_path()?, content.as_bytes())?;
+        Ok(())
+    }
+}
+
+/// Returns the configuration directory of the zitadel CLI
+pub(crate) fn config_dir() -> Result<PathBuf, ZitadelCLIError> {
+    let base_dir = match std::env::var("XDG_CONFIG_HOME") {
+        Ok(config_home) if !config_home.is_empty() => PathBuf::from(config_home),
+        _ => std::env::var("HOME")
+            .map(|home| PathBuf::from(home).join(".config"))
+            .map_err(|_| {
+                ZitadelCLIError::Context(
+                    "Neither XDG_CONFIG_HOME nor HOME is set, can't locate the contexts"
+                        .to_string(),
+                )
+            })?,
+    };
+    Ok(base_dir.join("zitadel-cli"))
+}
+
+/// Returns the path of the file holding the contexts
+fn store_file_path() -> Result<PathBuf, ZitadelCLIError> {
+    Ok(config_dir()?.join("config.toml"))
+}
+
+/// Returns the directory of the credentials cached by the contexts
+fn credentials_dir() -> Result<PathBuf, ZitadelCLIError> {
+    Ok(config_dir()?.join("credentials"))
+}
+
+/// Whether `path` is inside the credentials directory, only those files are deleted with their context
+pub(crate) fn is_cached_credentials(path: &Path) -> Result<bool, ZitadelCLIError> {
+    Ok(path.starts_with(credentials_dir()?)
+        && path
+            .components()
+            .all(|component| component != Component::ParentDir))
+}
+
+/// Returns the default path of the file the credentials of the context `name` are cached in
+/// - Returns `Err(ZitadelCLIError::Context(error))` if the name could point outside of the credentials directory
+pub(crate) fn default_credentials_file_path(name: &str) -> Result<PathBuf, ZitadelCLIError> {
+    if name.is_empty() || name.contains(['/', '\\']) || name.contains("..") {
+        return Err(ZitadelCLIError::Context(format!(
+            "The context name {name:?} can't be empty or contain `/`, `\\` or `..`"
+        )));
+    }
+    Ok(credentials_dir()?.join(format!("{name}.json")))
+}
+
+/// Returns the context used by the current invocation
+/// - `name`: The context passed with `--context`, the current context of the store is used if missing
+///
+/// Without any context, the configuration is read from the environment variables and CLI flags only
+pub(crate) fn resolve(name: Option<&str>) -> Result<Option<Context>, ZitadelCLIError> {
+    let mut store = ContextStore::load()?;
+    match name.map(str::to_string).or(store.current_context.clone()) {
+        Some(name) => Ok(Some(store.contexts.remove(&name).ok_or(
+            ZitadelCLIError::Context(format!("The context {name} doesn't exist")),
+        )?)),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_store_round_trip() {
+        let content = r#"
+current_context = "dev"
+
+[contexts.dev]
+issuer = "https://dev.zitadel.cloud"
+client_id = "client_id"
+flow = "authorization-code"
+scopes = "openid offline_access"
+credentials_file_path = "/tmp/dev.json"
+"#;
+        let store: ContextStore = toml::from_str(content).unwrap();
+        assert_eq!(store.current_context.as_deref(), Some("dev"));
+        let context = &store.contexts["dev"];
+        assert_eq!(context.flow, Some(Flow::AuthorizationCode));
+        assert_eq!(context.client_secret, None);
+
+        let reloaded: ContextStore = toml::from_str(&toml::to_string(&store).unwrap()).unwrap();
+        assert_eq!(&reloaded.contexts["dev"], context);
+    }
+
+    #[test]
+    fn test_credentials_file_name() {
+        for name in ["", "../prod", "a/b", "a\\b", ".."] {
+            assert!(
+                matches!(
+                    default_credentials_file_path(name),
+                    Err(ZitadelCLIError::Context(_))
+                ),
+                "{name}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_is_cached_credentials() {
+        let path = default_credentials_file_path("dev").unwrap();
+        assert!(is_cached_credentials(&path).unwrap());
+        let outside = path.with_file_name("..").join("config.toml");
+        assert!(!is_cached_credentials(&outside).unwrap());
+        assert!(!is_cached_credentials(Path::new("/etc/hosts")).unwrap());
+    }
+}
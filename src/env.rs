@@ -1,59 +1,78 @@
 //! This module contains the environment variables for the zitadel CLI
+//! They are one of the layers of the [`Config`], empty variables are considered unset
 
-use std::path::PathBuf;
-/// Returns the path to the configuration file
-/// Necessary for all flows
-/// The configuration file is used to store the access token
-/// The `CONFIG_FILE_PATH` environment variable must be an absolute path
-pub(crate) fn config_file_path() -> PathBuf {
-    std::env::var("CONFIG_FILE_PATH")
-        .expect("CONFIG_FILE_PATH env variable not found")
-        .parse()
-        .expect("Failed to parse CONFIG_FILE_PATH")
-}
-
-/// Returns the issuer
-/// Necessary for all flows
-pub(crate) fn issuer() -> String {
-    std::env::var("ISSUER").expect("ISSUER env variable not found")
-}
+use std::{collections::BTreeMap, path::PathBuf};
 
-/// Returns the client id
-/// Necessary for `Authorization Code Flow` and `Client Credential Flow`
-pub(crate) fn client_id() -> String {
-    std::env::var("CLIENT_ID").expect("CLIENT_ID env variable not found")
-}
+use clap::ValueEnum;
 
-/// Returns the client secret
-/// Necessary for `Authorization Code Flow`
-pub(crate) fn client_secret() -> String {
-    std::env::var("CLIENT_SECRET").expect("CLIENT_SECRET env variable not found")
-}
-
-/// Returns the callback server address
-/// Necessary for `Authorization Code Flow`
-pub(crate) fn callback_server_address() -> String {
-    std::env::var("CALLBACK_SERVER_ADDRESS")
-        .expect("CALLBACK_SERVER_ADDRESS env variable not found")
-}
+use crate::{
+    commands::flows::Flow,
+    config::{Config, ConfigKey},
+};
 
-/// Returns the scopes
-/// Necessary for all flows
-pub(crate) fn scopes() -> String {
-    std::env::var("SCOPES").expect("SCOPES env variable not found")
+/// Returns the value of the environment variable of `key`, if set and not empty
+fn var(key: ConfigKey) -> Option<String> {
+    std::env::var(key.env_name())
+        .ok()
+        .filter(|value| !value.is_empty())
 }
 
-/// Returns whether the server is secure or not (https or http)
-/// Necessary for `Authorization Code Flow`
-pub(crate) fn is_secure() -> bool {
-    std::env::var("IS_SECURE")
-        .unwrap_or_else(|_| "false".to_string())
-        .parse::<bool>()
-        .unwrap_or(false)
+/// Parses the environment variable of `key`, the parsing error is recorded in `malformed`
+fn parse_var<T>(
+    key: ConfigKey,
+    parse: impl Fn(&str) -> Result<T, String>,
+    malformed: &mut BTreeMap<ConfigKey, String>,
+) -> Option<T> {
+    var(key).and_then(|value| match parse(&value) {
+        Ok(parsed) => Some(parsed),
+        Err(error) => {
+            malformed.insert(key, error);
+            None
+        }
+    })
 }
 
-/// Returns the personal access token
-/// Necessary for `Personal Access Token Flow`
-pub(crate) fn personal_access_token() -> String {
-    std::env::var("PERSONAL_ACCESS_TOKEN").expect("PERSONAL_ACCESS_TOKEN env variable not found")
+/// Returns the configuration layer made of the environment variables, here is the full list:
+/// - `CONFIG_FILE_PATH` The path to the file used to store the access token, necessary for all flows
+/// - `ISSUER` The url of the Zitadel instance, necessary for all flows
+/// - `CLIENT_ID` Necessary for `Authorization Code Flow`, `Client Credential Flow` and `Device Code Flow`
+/// - `CLIENT_SECRET` Necessary for `Client Credential Flow`
+/// - `CALLBACK_SERVER_ADDRESS` Necessary for `Authorization Code Flow`
+/// - `SCOPES` Necessary for all flows except `Personal Access Token Flow`
+/// - `IS_SECURE` Whether the callback server is secure or not (https or http), defaults to `false`
+/// - `PERSONAL_ACCESS_TOKEN` Necessary for `Personal Access Token Flow`
+/// - `KEY_FILE_PATH` The path to the JSON key file of the service user, necessary for `JWT Profile Flow`
+/// - `FLOW` The flow used by `login` when `--flow` isn't specified
+/// - `ORG` The id or name of the organization targeted by the resource commands
+pub(crate) fn config() -> Config {
+    let mut malformed = BTreeMap::new();
+    let is_secure = parse_var(
+        ConfigKey::IsSecure,
+        |value| {
+            value
+                .parse::<bool>()
+                .map_err(|_| format!("expected `true` or `false`, got `{value}`"))
+        },
+        &mut malformed,
+    );
+    let flow = parse_var(
+        ConfigKey::Flow,
+        |value| Flow::from_str(value, true),
+        &mut malformed,
+    );
+    Config {
+        config_file_path: var(ConfigKey::ConfigFilePath).map(PathBuf::from),
+        issuer: var(ConfigKey::Issuer),
+        client_id: var(ConfigKey::ClientId),
+        client_secret: var(ConfigKey::ClientSecret),
+        callback_server_address: var(ConfigKey::CallbackServerAddress),
+        scopes: var(ConfigKey::Scopes),
+        is_secure,
+        personal_access_token: var(ConfigKey::PersonalAccessToken),
+        key_file_path: var(ConfigKey::KeyFilePath).map(PathBuf::from),
+        flow,
+        org: var(ConfigKey::Org),
+        malformed,
+        ..Default::default()
+    }
 }
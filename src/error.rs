@@ -1,17 +1,322 @@
 //! Error handling for the Zitadel CLI
 
+use std::fmt::Display;
+
+use reqwest::StatusCode;
+use serde::Deserialize;
+use serde_json::Value;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum ZitadelCLIError {
     #[error("Network error: {0}")]
     Reqwest(#[from] reqwest::Error),
-    #[error("Invalid response: {0}")]
-    ReqwestResponse(String),
+    /// An error of the OAuth endpoints of the login flows, with the HTTP status if the endpoint answered
+    #[error("The authorization server rejected the request: {1}")]
+    OAuth(Option<StatusCode>, String),
+    #[error("The Zitadel API rejected the request: {0}")]
+    Api(ApiError),
     #[error("IO error: {0}")]
     IO(#[from] std::io::Error),
     #[error("Failed to parse url: {0}")]
     URLParse(#[from] url::ParseError),
-    #[error("Failed to parse json:  {0}")]
+    /// A local file that can't be parsed, or a value that can't be serialized
+    #[error("Failed to read or write json: {0}")]
     JSONParse(#[from] serde_json::Error),
+    #[error("Invalid response of the Zitadel API: {0}")]
+    InvalidResponse(serde_json::Error),
+    #[error("Failed to parse toml: {0}")]
+    TOMLParse(#[from] toml::de::Error),
+    #[error("Failed to serialize toml: {0}")]
+    TOMLSerialize(#[from] toml::ser::Error),
+    #[error("Failed to serialize yaml: {0}")]
+    YAMLSerialize(#[from] serde_yaml::Error),
+    #[error("Not logged in, no access token found in {}, run `login` first", .0.display())]
+    NotLoggedIn(std::path::PathBuf),
+    #[error("Context error: {0}")]
+    Context(String),
+    #[error("Organization error: {0}")]
+    Organization(String),
+    #[error("Invalid configuration:\n- {}", .0.join("\n- "))]
+    Config(Vec<String>),
+    #[error("Invalid manifest: {0}")]
+    Manifest(String),
+    #[error("Invalid payload: {0}")]
+    Payload(String),
+    #[error("Import failed: {0}")]
+    Import(String),
+    #[error("Failed to decode base64: {0}")]
+    Base64Decode(#[from] base64::DecodeError),
+    #[error("Failed to sign the JWT: {0}")]
+    JWTSign(#[from] jsonwebtoken::errors::Error),
+}
+
+/// The exit codes of the process, by class of failure
+pub mod exit_code {
+    /// Any other failure, e.g. a file that can't be read or written
+    pub const GENERAL: u8 = 1;
+    /// Invalid arguments or configuration, also used by clap for the invalid arguments
+    pub const USAGE: u8 = 2;
+    /// Not logged in, invalid or expired token, or missing permissions
+    pub const AUTH: u8 = 3;
+    /// The resource or organization doesn't exist
+    pub const NOT_FOUND: u8 = 4;
+    /// The resource already exists or isn't in a state allowing the operation
+    pub const CONFLICT: u8 = 5;
+    /// The payload was rejected by the API
+    pub const VALIDATION: u8 = 6;
+    /// The Zitadel instance couldn't be reached
+    pub const NETWORK: u8 = 7;
+    /// The Zitadel instance failed to handle the request or sent an invalid response
+    pub const SERVER: u8 = 8;
+}
+
+impl ZitadelCLIError {
+    /// Returns the exit code of the process for the error, see [`exit_code`]
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            ZitadelCLIError::Api(error) => error.exit_code(),
+            // A response whose body can't be decoded was sent by the instance
+            ZitadelCLIError::Reqwest(error) if error.is_decode() => exit_code::SERVER,
+            ZitadelCLIError::Reqwest(_) => exit_code::NETWORK,
+            ZitadelCLIError::OAuth(Some(status), _) if status.is_server_error() => {
+                exit_code::SERVER
+            }
+            ZitadelCLIError::OAuth(..)
+            | ZitadelCLIError::JWTSign(_)
+            | ZitadelCLIError::NotLoggedIn(_) => exit_code::AUTH,
+            ZitadelCLIError::URLParse(_)
+            | ZitadelCLIError::TOMLParse(_)
+            | ZitadelCLIError::Context(_)
+            | ZitadelCLIError::Config(_) => exit_code::USAGE,
+            ZitadelCLIError::Organization(_) => exit_code::NOT_FOUND,
+            ZitadelCLIError::Manifest(_)
+            | ZitadelCLIError::Payload(_)
+            | ZitadelCLIError::Import(_) => exit_code::VALIDATION,
+            ZitadelCLIError::Base64Decode(_) | ZitadelCLIError::InvalidResponse(_) => {
+                exit_code::SERVER
+            }
+            ZitadelCLIError::IO(_)
+            | ZitadelCLIError::JSONParse(_)
+            | ZitadelCLIError::TOMLSerialize(_)
+            | ZitadelCLIError::YAMLSerialize(_) => exit_code::GENERAL,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+/// An error returned by the Zitadel API, in the format of the gRPC gateway
+pub struct ApiError {
+    #[serde(skip, default = "default_status")]
+    pub status: StatusCode,
+    /// The gRPC status code
+    #[serde(default)]
+    pub code: i32,
+    #[serde(default)]
+    pub message: String,
+    #[serde(default)]
+    pub details: Vec<Value>,
+}
+
+fn default_status() -> StatusCode {
+    StatusCode::INTERNAL_SERVER_ERROR
+}
+
+impl ApiError {
+    /// Parses the body of an error response, returns `None` if it isn't a gRPC gateway error
+    pub fn parse(status: StatusCode, body: &str) -> Option<ApiError> {
+        let error = serde_json::from_str::<ApiError>(body).ok()?;
+        (error.code != 0 || !error.message.is_empty()).then_some(ApiError { status, ..error })
+    }
+
+    /// Decodes the body of an error response, the gRPC code is derived from the HTTP status if it isn't a gRPC gateway error
+    pub fn from_response(status: StatusCode, body: &str) -> ApiError {
+        ApiError::parse(status, body).unwrap_or_else(|| ApiError {
+            status,
+            code: match status {
+                StatusCode::BAD_REQUEST => 3,
+                StatusCode::NOT_FOUND => 5,
+                StatusCode::CONFLICT => 6,
+                StatusCode::FORBIDDEN => 7,
+                StatusCode::TOO_MANY_REQUESTS => 8,
+                StatusCode::NOT_IMPLEMENTED => 12,
+                StatusCode::BAD_GATEWAY
+                | StatusCode::SERVICE_UNAVAILABLE
+                | StatusCode::GATEWAY_TIMEOUT => 14,
+                StatusCode::UNAUTHORIZED => 16,
+                _ => 2,
+            },
+            message: body.to_string(),
+            details: vec![],
+        })
+    }
+
+    /// Returns the exit code of the process for the gRPC status code
+    fn exit_code(&self) -> u8 {
+        match self.code {
+            7 | 16 => exit_code::AUTH,
+            5 => exit_code::NOT_FOUND,
+            6 | 9 | 10 => exit_code::CONFLICT,
+            3 | 11 => exit_code::VALIDATION,
+            _ => exit_code::SERVER,
+        }
+    }
+
+    /// Returns the name of the gRPC status code
+    pub fn code_name(&self) -> &'static str {
+        match self.code {
+            1 => "CANCELLED",
+            2 => "UNKNOWN",
+            3 => "INVALID_ARGUMENT",
+            4 => "DEADLINE_EXCEEDED",
+            5 => "NOT_FOUND",
+            6 => "ALREADY_EXISTS",
+            7 => "PERMISSION_DENIED",
+            8 => "RESOURCE_EXHAUSTED",
+            9 => "FAILED_PRECONDITION",
+            10 => "ABORTED",
+            11 => "OUT_OF_RANGE",
+            12 => "UNIMPLEMENTED",
+            13 => "INTERNAL",
+            14 => "UNAVAILABLE",
+            15 => "DATA_LOSS",
+            16 => "UNAUTHENTICATED",
+            _ => "UNKNOWN",
+        }
+    }
+
+    /// Returns a human explanation of the gRPC status code
+    fn explanation(&self) -> &'static str {
+        match self.code {
+            3 | 11 => "The payload is invalid, check the field below against the API documentation",
+            4 | 14 => "The Zitadel instance is unavailable or too slow, try again later",
+            5 => "The resource doesn't exist, or doesn't belong to the targeted organization (see `--org`)",
+            6 => "A resource with the same unique value (e.g. name, username or domain) already exists",
+            7 => "The logged in user lacks the permission, check its member roles",
+            8 => "Too many requests were sent, try again later",
+            9 => "The resource isn't in a state allowing this operation (e.g. it is already deactivated)",
+            10 => "The resource was modified concurrently, try again",
+            12 => "The Zitadel instance doesn't support this operation, it may be too old",
+            16 => "The token is invalid or expired, please log in again",
+            _ => "The Zitadel instance failed to handle the request",
+        }
+    }
+
+    /// Returns the path of the field that caused the error, in the casing of the JSON payload (e.g. `profile.givenName`)
+    /// It comes from a `BadRequest` detail if any, from the parsing or validation message otherwise
+    pub fn field(&self) -> Option<String> {
+        let violation = self
+            .details
+            .iter()
+            .filter_map(|detail| detail.get("fieldViolations")?.as_array())
+            .flatten()
+            .find_map(|violation| violation.get("field")?.as_str());
+        if let Some(field) = violation {
+            return Some(field.to_string());
+        }
+        // The JSON parsing errors look like `proto: (line 1:109): error parsing "orgDomain", oneof ... is already set`
+        if let Some((_, rest)) = self.message.split_once("error parsing \"") {
+            return rest.split('"').next().map(str::to_string);
+        }
+        // The validation messages look like
+        // `invalid AddHumanUserRequest.Profile: embedded message failed validation | caused by: invalid SetHumanProfile.GivenName: ...`
+        let path: Vec<String> = self
+            .message
+            .split("invalid ")
+            .skip(1)
+            .filter_map(|part| part.split(':').next()?.split_once('.'))
+            .map(|(_, field)| lower_camel_case(field))
+            .collect();
+        (!path.is_empty()).then(|| path.join("."))
+    }
+}
+
+/// Converts a protobuf field name (e.g. `GivenName`) to the name of the JSON field (e.g. `givenName`)
+fn lower_camel_case(field: &str) -> String {
+    let mut characters = field.chars();
+    match characters.next() {
+        Some(first) => first.to_lowercase().chain(characters).collect(),
+        None => String::new(),
+    }
+}
+
+impl Display for ApiError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} ({}): {}\n{}",
+            self.code_name(),
+            self.status.as_u16(),
+            self.message,
+            self.explanation()
+        )?;
+        if let Some(field) = self.field() {
+            write!(f, "\nOffending field: {field}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_api_error() {
+        let body = r#"{
+            "code": 3,
+            "message": "invalid AddHumanUserRequest.Profile: embedded message failed validation | caused by: invalid SetHumanProfile.GivenName: value length must be between 1 and 200 runes, inclusive",
+            "details": [{ "@type": "type.googleapis.com/zitadel.v1.ErrorDetail", "id": "VALID-1" }]
+        }"#;
+        let error = ApiError::parse(StatusCode::BAD_REQUEST, body).unwrap();
+        assert_eq!(error.code_name(), "INVALID_ARGUMENT");
+        assert_eq!(error.field().as_deref(), Some("profile.givenName"));
+
+        let body = r#"{"code": 6, "message": "Errors.User.AlreadyExists (COMMAND-k2unb)"}"#;
+        let error = ApiError::parse(StatusCode::CONFLICT, body).unwrap();
+        assert_eq!(error.code_name(), "ALREADY_EXISTS");
+        assert_eq!(error.field(), None);
+
+        let body = r#"{"code": 3, "message": "proto: (line 1:109): error parsing \"orgDomain\", oneof zitadel.object.v2.Organization.org is already set"}"#;
+        let error = ApiError::parse(StatusCode::BAD_REQUEST, body).unwrap();
+        assert_eq!(error.field().as_deref(), Some("orgDomain"));
+
+        assert!(ApiError::parse(StatusCode::BAD_GATEWAY, "<html>Bad Gateway</html>").is_none());
+    }
+
+    #[test]
+    fn test_exit_code() {
+        let error = |status, body| ZitadelCLIError::Api(ApiError::from_response(status, body));
+        assert_eq!(
+            error(StatusCode::CONFLICT, r#"{"code": 6, "message": "exists"}"#).exit_code(),
+            exit_code::CONFLICT
+        );
+        assert_eq!(
+            error(StatusCode::UNAUTHORIZED, "").exit_code(),
+            exit_code::AUTH
+        );
+        assert_eq!(
+            error(StatusCode::BAD_GATEWAY, "<html>Bad Gateway</html>").exit_code(),
+            exit_code::SERVER
+        );
+        assert_eq!(
+            ZitadelCLIError::Config(vec![]).exit_code(),
+            exit_code::USAGE
+        );
+        let oauth = |status| ZitadelCLIError::OAuth(status, String::new());
+        assert_eq!(
+            oauth(Some(StatusCode::BAD_REQUEST)).exit_code(),
+            exit_code::AUTH
+        );
+        assert_eq!(
+            oauth(Some(StatusCode::BAD_GATEWAY)).exit_code(),
+            exit_code::SERVER
+        );
+        assert_eq!(oauth(None).exit_code(), exit_code::AUTH);
+        let malformed = serde_json::from_str::<Value>("<html>").unwrap_err();
+        assert_eq!(
+            ZitadelCLIError::InvalidResponse(malformed).exit_code(),
+            exit_code::SERVER
+        );
+    }
 }
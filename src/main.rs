@@ -1,18 +1,30 @@
 use cli_parser::parse_cli;
 use commands::logout;
-use payloads::idp::google::NewGoogleIdp;
-use payloads::{organization::NewOrganization, project::NewProject};
+use std::process::ExitCode;
+use tracing::error;
 
 mod cli_parser;
+mod client;
 mod commands;
+mod config;
+mod context;
 mod env;
 mod error;
+mod output;
 mod payloads;
 
+/// Runs the CLI, the process exits with the code of the error class if the command fails, see [`error::ZitadelCLIError::exit_code`]
 #[actix_web::main]
-async fn main() -> std::io::Result<()> {
-    tracing_subscriber::fmt::init();
+async fn main() -> ExitCode {
+    tracing_subscriber::fmt()
+        .with_writer(std::io::stderr)
+        .init();
     dotenvy::dotenv().ok();
-    parse_cli().await;
-    Ok(())
+    match parse_cli().await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            error!("{error}");
+            ExitCode::from(error.exit_code())
+        }
+    }
 }
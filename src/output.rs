@@ -0,0 +1,254 @@
+//! This module prints the responses of the Zitadel API on stdout, in the format selected with `--output`
+//! The logs are written to stderr so the output can be piped to other tools
+
+use clap::ValueEnum;
+use comfy_table::{presets::UTF8_FULL_CONDENSED, Table};
+use serde_json::{Map, Value};
+
+use crate::error::ZitadelCLIError;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+/// The formats the responses can be printed in
+pub(crate) enum OutputFormat {
+    /// A table, one row per resource
+    Table,
+    /// The JSON response of the API
+    #[default]
+    Json,
+    /// The response of the API converted to YAML
+    Yaml,
+    /// Only the id of each resource, one per line
+    Id,
+    /// One compact JSON resource per line, the pages of a list are printed as soon as they are received
+    Ndjson,
+}
+
+#[derive(Debug, Clone, Default)]
+/// How the responses are printed
+pub(crate) struct Output {
+    pub(crate) format: OutputFormat,
+    /// The fields kept in each resource, nested fields are separated by dots (e.g. `details.sequence`)
+    /// Every field is kept if empty
+    pub(crate) fields: Vec<String>,
+}
+
+impl Output {
+    /// Prints `value` on stdout
+    pub(crate) fn print(&self, value: &Value) -> Result<(), ZitadelCLIError> {
+        let rendered = self.render(value)?;
+        if !rendered.is_empty() {
+            println!("{}", rendered.trim_end());
+        }
+        Ok(())
+    }
+
+    /// Returns the output keeping every field, the ids are printed as JSON instead
+    /// Used for the secrets, which Zitadel only shows once and must never be filtered out
+    pub(crate) fn unfiltered(&self) -> Output {
+        Output {
+            format: match self.format {
+                OutputFormat::Id => OutputFormat::Json,
+                format => format,
+            },
+            fields: vec![],
+        }
+    }
+
+    /// Renders `value` in the output format, keeping only the selected fields
+    /// A response holding a `result` array is considered a list of resources
+    pub(crate) fn render(&self, value: &Value) -> Result<String, ZitadelCLIError> {
+        let rows = self.rows(value);
+        let selected = |rows| match (self.fields.is_empty(), rows) {
+            (true, _) => value.clone(),
+            (false, Rows::List(items)) => Value::Array(items),
+            (false, Rows::Single(item)) => item,
+        };
+        Ok(match self.format {
+            OutputFormat::Json => serde_json::to_string_pretty(&selected(rows))?,
+            OutputFormat::Yaml => serde_yaml::to_string(&selected(rows))?,
+            OutputFormat::Ndjson => match rows {
+                Rows::List(items) => items
+                    .iter()
+                    .map(serde_json::to_string)
+                    .collect::<Result<Vec<_>, _>>()?
+                    .join("\n"),
+                Rows::Single(item) => serde_json::to_string(&item)?,
+            },
+            OutputFormat::Id => match rows {
+                Rows::List(items) => items.iter().filter_map(id).collect::<Vec<_>>().join("\n"),
+                Rows::Single(item) => id(&item).unwrap_or_default(),
+            },
+            OutputFormat::Table => match rows {
+                Rows::List(items) => list_table(&items).to_string(),
+                Rows::Single(item) => item_table(&item).to_string(),
+            },
+        })
+    }
+
+    /// Splits the response into its resources, with only the selected fields
+    fn rows(&self, value: &Value) -> Rows {
+        let items = match value {
+            Value::Array(items) => Some(items),
+            value => value.get("result").and_then(Value::as_array),
+        };
+        match items {
+            Some(items) => Rows::List(items.iter().map(|item| self.select(item)).collect()),
+            None => Rows::Single(self.select(value)),
+        }
+    }
+
+    /// Keeps only the selected fields of `item`, a missing field is set to `null`
+    fn select(&self, item: &Value) -> Value {
+        if self.fields.is_empty() {
+            return item.clone();
+        }
+        let selected = self
+            .fields
+            .iter()
+            .map(|field| {
+                (
+                    field.clone(),
+                    lookup(item, field).cloned().unwrap_or(Value::Null),
+                )
+            })
+            .collect::<Map<_, _>>();
+        Value::Object(selected)
+    }
+}
+
+enum Rows {
+    List(Vec<Value>),
+    Single(Value),
+}
+
+/// Returns the value at the dotted `path` of `item`
+fn lookup<'a>(item: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.').try_fold(item, |value, key| value.get(key))
+}
+
+/// Returns the id of a resource: the `id` field, or the first field ending with `Id` (e.g. `userId`, `appId`)
+/// The resources wrapped in an object (e.g. `{"project": {...}}`) are searched one level deeper
+fn id(item: &Value) -> Option<String> {
+    let object = item.as_object()?;
+    object
+        .get("id")
+        .or_else(|| {
+            object
+                .iter()
+                .find(|(key, _)| key.ends_with("Id"))
+                .map(|(_, value)| value)
+        })
+        .map(cell)
+        .or_else(|| {
+            object
+                .iter()
+                .filter(|(key, _)| key.as_str() != "details")
+                .find_map(|(_, value)| value.is_object().then(|| id(value)).flatten())
+        })
+}
+
+/// Returns the text of a table cell, the strings are unquoted and the other values written as compact JSON
+fn cell(value: &Value) -> String {
+    match value {
+        Value::String(string) => string.clone(),
+        Value::Null => String::new(),
+        value => value.to_string(),
+    }
+}
+
+/// Builds a table with one row per resource and one column per field
+fn list_table(items: &[Value]) -> Table {
+    let mut columns: Vec<&String> = vec![];
+    for object in items.iter().filter_map(Value::as_object) {
+        for key in object.keys() {
+            if !columns.contains(&key) {
+                columns.push(key);
+            }
+        }
+    }
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL_CONDENSED).set_header(&columns);
+    for item in items {
+        table.add_row(
+            columns
+                .iter()
+                .map(|column| item.get(column.as_str()).map(cell).unwrap_or_default()),
+        );
+    }
+    table
+}
+
+/// Builds a table with one row per field of the resource
+fn item_table(item: &Value) -> Table {
+    let mut table = Table::new();
+    table.load_preset(UTF8_FULL_CONDENSED);
+    match item.as_object() {
+        Some(object) => {
+            for (key, value) in object {
+                table.add_row(vec![key.clone(), cell(value)]);
+            }
+        }
+        None => {
+            table.add_row(vec![cell(item)]);
+        }
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_render_ids_and_fields() {
+        let list = json!({
+            "details": { "totalResult": "2" },
+            "result": [
+                { "userId": "1", "username": "minnie", "human": { "email": { "email": "minnie@mouse.com" } } },
+                { "userId": "2", "username": "mickey" }
+            ]
+        });
+        let output = Output {
+            format: OutputFormat::Id,
+            fields: vec![],
+        };
+        assert_eq!(output.render(&list).unwrap(), "1\n2");
+        assert_eq!(
+            output.render(&json!({ "project": { "id": "3" } })).unwrap(),
+            "3"
+        );
+
+        let output = Output {
+            format: OutputFormat::Json,
+            fields: vec!["username".to_string(), "human.email.email".to_string()],
+        };
+        let rendered: Value = serde_json::from_str(&output.render(&list).unwrap()).unwrap();
+        assert_eq!(
+            rendered,
+            json!([
+                { "username": "minnie", "human.email.email": "minnie@mouse.com" },
+                { "username": "mickey", "human.email.email": null }
+            ])
+        );
+
+        let output = Output {
+            format: OutputFormat::Ndjson,
+            fields: vec!["userId".to_string()],
+        };
+        assert_eq!(
+            output.render(&list).unwrap(),
+            "{\"userId\":\"1\"}\n{\"userId\":\"2\"}"
+        );
+
+        let output = Output {
+            format: OutputFormat::Id,
+            fields: vec!["tokenId".to_string()],
+        };
+        let secret = json!({ "tokenId": "1", "token": "secret" });
+        let rendered: Value =
+            serde_json::from_str(&output.unfiltered().render(&secret).unwrap()).unwrap();
+        assert_eq!(rendered, secret);
+    }
+}
@@ -0,0 +1,16 @@
+//! This module contains the structure of the actions, scripts run by Zitadel during its flows
+//! For more infos see [the Zitadel documentation](https://zitadel.com/docs/apis/resources/mgmt/management-service-create-action)
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+/// The structure of the action to be created or updated
+pub(crate) struct NewAction {
+    pub(crate) name: String,
+    /// The JavaScript code of the action, it defines a function with the name of the action
+    script: String,
+    /// e.g. `10s`
+    timeout: Option<String>,
+    allowed_to_fail: Option<bool>,
+}
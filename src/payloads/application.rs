@@ -0,0 +1,128 @@
+//! This module contains the structures for the applications of a project
+//! For more infos see [the Zitadel documentation](https://zitadel.com/docs/apis/resources/mgmt/applications)
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error::ZitadelCLIError;
+
+use super::normalize;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+/// The types of application
+pub(crate) enum ApplicationType {
+    Oidc,
+    Api,
+    Saml,
+}
+
+impl ApplicationType {
+    /// Returns the path of the application type in the management API
+    pub(crate) fn path(&self) -> &'static str {
+        match self {
+            ApplicationType::Oidc => "oidc",
+            ApplicationType::Api => "api",
+            ApplicationType::Saml => "saml",
+        }
+    }
+
+    /// Returns the field holding the configuration of the application type in the responses of the API
+    pub(crate) fn config_field(&self) -> &'static str {
+        match self {
+            ApplicationType::Oidc => "oidcConfig",
+            ApplicationType::Api => "apiConfig",
+            ApplicationType::Saml => "samlConfig",
+        }
+    }
+
+    /// Checks `spec` against the payload of the application type, see [`normalize`]
+    pub(crate) fn normalize(&self, spec: Value) -> Result<Value, ZitadelCLIError> {
+        match self {
+            ApplicationType::Oidc => normalize::<NewOidcApp>(spec),
+            ApplicationType::Api => normalize::<NewApiApp>(spec),
+            ApplicationType::Saml => normalize::<NewSamlApp>(spec),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+/// The structure of the OIDC application to be created
+/// For more details about each field, you can look at the [documentation](https://zitadel.com/docs/apis/resources/mgmt/management-service-add-oidc-app)
+pub(crate) struct NewOidcApp {
+    name: String,
+    #[serde(flatten)]
+    config: OidcAppConfig,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+/// The OIDC configuration of an application, shared by the creation and the update
+pub(crate) struct OidcAppConfig {
+    redirect_uris: Option<Vec<String>>,
+    /// e.g. `OIDC_RESPONSE_TYPE_CODE`
+    response_types: Option<Vec<String>>,
+    /// e.g. `OIDC_GRANT_TYPE_AUTHORIZATION_CODE`, `OIDC_GRANT_TYPE_REFRESH_TOKEN`, `OIDC_GRANT_TYPE_DEVICE_CODE`
+    grant_types: Option<Vec<String>>,
+    /// Either `OIDC_APP_TYPE_WEB`, `OIDC_APP_TYPE_USER_AGENT` or `OIDC_APP_TYPE_NATIVE`
+    app_type: Option<String>,
+    /// e.g. `OIDC_AUTH_METHOD_TYPE_BASIC`, `OIDC_AUTH_METHOD_TYPE_NONE` (PKCE)
+    auth_method_type: Option<String>,
+    post_logout_redirect_uris: Option<Vec<String>>,
+    version: Option<String>,
+    dev_mode: Option<bool>,
+    access_token_type: Option<String>,
+    access_token_role_assertion: Option<bool>,
+    id_token_role_assertion: Option<bool>,
+    id_token_userinfo_assertion: Option<bool>,
+    clock_skew: Option<String>,
+    additional_origins: Option<Vec<String>>,
+    skip_native_app_success_page: Option<bool>,
+    back_channel_logout_uri: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+/// The structure of the API application to be created
+/// For more details about each field, you can look at the [documentation](https://zitadel.com/docs/apis/resources/mgmt/management-service-add-api-app)
+pub(crate) struct NewApiApp {
+    name: String,
+    #[serde(flatten)]
+    config: ApiAppConfig,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+/// The API configuration of an application, shared by the creation and the update
+pub(crate) struct ApiAppConfig {
+    /// Either `API_AUTH_METHOD_TYPE_BASIC` or `API_AUTH_METHOD_TYPE_PRIVATE_KEY_JWT`
+    auth_method_type: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+/// The structure of the SAML application to be created
+/// For more details about each field, you can look at the [documentation](https://zitadel.com/docs/apis/resources/mgmt/management-service-add-saml-app)
+pub(crate) struct NewSamlApp {
+    name: String,
+    #[serde(flatten)]
+    config: SamlAppConfig,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+/// The SAML configuration of an application, shared by the creation and the update
+/// Either `metadataXml` or `metadataUrl` has to be specified
+pub(crate) struct SamlAppConfig {
+    /// The base64 encoded metadata of the service provider
+    metadata_xml: Option<String>,
+    metadata_url: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+/// The fields common to all the application types that can be updated
+pub(crate) struct UpdateApp {
+    name: String,
+}
@@ -0,0 +1,172 @@
+//! This module checks the password hashes migrated with `human-user import`, so the users keep their passwords
+//! Zitadel verifies the hashes in the modular crypt format or the PHC string format of the algorithms below, the
+//! algorithms have to be enabled in the `PasswordHasher.Verifiers` of the instance
+
+use std::fmt::Display;
+
+use clap::ValueEnum;
+
+/// The characters of the base64 alphabet used by bcrypt, MD5-crypt and PBKDF2
+const CRYPT_ALPHABET: &str = "./0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+/// The hash algorithms Zitadel verifies
+pub(crate) enum HashFormat {
+    /// `$2a$`, `$2b$` or `$2y$`, e.g. `$2b$12$<salt and hash>`
+    Bcrypt,
+    /// `$argon2i$` or `$argon2id$`, e.g. `$argon2id$v=19$m=65536,t=3,p=4$<salt>$<hash>`
+    Argon2,
+    /// e.g. `$scrypt$ln=15,r=8,p=1$<salt>$<hash>`
+    Scrypt,
+    /// `$pbkdf2$` (SHA-1) or `$pbkdf2-sha224$`, `-sha256$`, `-sha384$`, `-sha512$`, e.g. `$pbkdf2-sha256$29000$<salt>$<hash>`
+    Pbkdf2,
+    /// e.g. `$1$<salt>$<hash>`
+    Md5Crypt,
+}
+
+impl Display for HashFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            HashFormat::Bcrypt => "bcrypt",
+            HashFormat::Argon2 => "argon2",
+            HashFormat::Scrypt => "scrypt",
+            HashFormat::Pbkdf2 => "PBKDF2",
+            HashFormat::Md5Crypt => "MD5-crypt",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl HashFormat {
+    /// Returns the algorithm of a hash, checking that Zitadel can parse it
+    /// - Returns `Err(error)` describing why the hash isn't accepted
+    pub(crate) fn detect(hash: &str) -> Result<HashFormat, String> {
+        let parts: Vec<&str> = hash.split('$').collect();
+        let (format, valid) = match parts.as_slice() {
+            ["", "2a" | "2b" | "2y", cost, salt_and_hash] => (HashFormat::Bcrypt, bcrypt(cost, salt_and_hash)),
+            ["", "argon2i" | "argon2id", version, params, salt, hash] => (
+                HashFormat::Argon2,
+                *version == "v=19"
+                    && has_params(params, &["m", "t", "p"])
+                    && is_base64(salt)
+                    && is_base64(hash),
+            ),
+            ["", "scrypt", params, salt, hash] => (
+                HashFormat::Scrypt,
+                has_params(params, &["ln", "r", "p"]) && is_base64(salt) && is_base64(hash),
+            ),
+            ["", variant, rounds, salt, hash] if variant.starts_with("pbkdf2") => {
+                (HashFormat::Pbkdf2, pbkdf2(variant, rounds, salt, hash))
+            }
+            ["", "1", salt, hash] => (
+                HashFormat::Md5Crypt,
+                salt.len() <= 8 && hash.len() == 22 && is_crypt_base64(hash),
+            ),
+            _ => {
+                return Err(
+                    "the hash isn't a bcrypt, argon2, scrypt, PBKDF2 or MD5-crypt hash in the modular crypt format"
+                        .to_string(),
+                )
+            }
+        };
+        match valid {
+            true => Ok(format),
+            false => Err(format!("the {format} hash is malformed")),
+        }
+    }
+}
+
+/// Checks the cost, from 4 to 31, and the 22 characters of salt followed by the 31 characters of hash
+fn bcrypt(cost: &str, salt_and_hash: &str) -> bool {
+    cost.len() == 2
+        && cost
+            .parse::<u8>()
+            .is_ok_and(|cost| (4..=31).contains(&cost))
+        && salt_and_hash.len() == 53
+        && is_crypt_base64(salt_and_hash)
+}
+
+/// Checks the digest, the number of rounds and that the hash has the length of the digest
+fn pbkdf2(variant: &str, rounds: &str, salt: &str, hash: &str) -> bool {
+    let digest_size: usize = match variant {
+        "pbkdf2" => 20,
+        "pbkdf2-sha224" => 28,
+        "pbkdf2-sha256" => 32,
+        "pbkdf2-sha384" => 48,
+        "pbkdf2-sha512" => 64,
+        _ => return false,
+    };
+    rounds.parse::<u32>().is_ok_and(|rounds| rounds > 0)
+        && !salt.is_empty()
+        && is_crypt_base64(salt)
+        && hash.len() == (digest_size * 4).div_ceil(3)
+        && is_crypt_base64(hash)
+}
+
+/// Checks that the comma separated parameters are exactly `names` with integer values, e.g. `m=65536,t=3,p=4`
+fn has_params(params: &str, names: &[&str]) -> bool {
+    let params: Vec<_> = params
+        .split(',')
+        .map(|param| param.split_once('='))
+        .collect();
+    params.len() == names.len()
+        && params.iter().zip(names).all(|(param, name)| {
+            param.is_some_and(|(key, value)| key == *name && value.parse::<u32>().is_ok())
+        })
+}
+
+/// Checks that `value` is standard base64 without padding, as in the PHC string format
+fn is_base64(value: &str) -> bool {
+    !value.is_empty()
+        && value.chars().all(|character| {
+            character.is_ascii_alphanumeric() || character == '+' || character == '/'
+        })
+}
+
+/// Checks that `value` only has characters of [`CRYPT_ALPHABET`]
+fn is_crypt_base64(value: &str) -> bool {
+    value
+        .chars()
+        .all(|character| CRYPT_ALPHABET.contains(character))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect() {
+        let hashes = [
+            (
+                "$2b$12$EXRkfkdmXn2gzds2SSitu.MW9.gAVqa9eLS1//RYtYCmB1eLHg.9q",
+                HashFormat::Bcrypt,
+            ),
+            (
+                "$argon2id$v=19$m=65536,t=3,p=4$c2FsdHNhbHRzYWx0c2FsdA$LXEWQrcmsEQBYnyp+6wy9chTD7GQPMTbAiWHF5IaSIE",
+                HashFormat::Argon2,
+            ),
+            (
+                "$scrypt$ln=15,r=8,p=1$c2FsdHNhbHRzYWx0c2FsdA$I08hlG92LDYoMl/9VKf0zgfMRMPXjFzYk3rXjFEs85WAEHqcNTSNMMPFxwaZguOuxT6kj7HNrAZ95lnXPBkX5A",
+                HashFormat::Scrypt,
+            ),
+            (
+                "$pbkdf2-sha256$29000$c2FsdHNhbHRzYWx0c2FsdA$7xwbY5rCP.qJhnvJ80W3FI7hSRg8wNnl3S9rczjVuCk",
+                HashFormat::Pbkdf2,
+            ),
+            ("$1$saltsalt$qjXMvbEw8oaL.CzflDtaK/", HashFormat::Md5Crypt),
+        ];
+        for (hash, format) in hashes {
+            assert_eq!(HashFormat::detect(hash), Ok(format), "{hash}");
+        }
+
+        assert_eq!(
+            HashFormat::detect("$2b$99$EXRkfkdmXn2gzds2SSitu.MW9.gAVqa9eLS1//RYtYCmB1eLHg.9q"),
+            Err("the bcrypt hash is malformed".to_string())
+        );
+        assert!(HashFormat::detect(
+            "$pbkdf2-sha512$29000$c2FsdA$7xwbY5rCP.qJhnvJ80W3FI7hSRg8wNnl3S9rczjVuCk"
+        )
+        .is_err());
+        assert!(HashFormat::detect("5f4dcc3b5aa765d61d8327deb882cf99").is_err());
+    }
+}
@@ -0,0 +1,20 @@
+//! This module contains the structure to add or update an Apple identity provider
+//! For more infos see [the Zitadel documentation](https://zitadel.com/docs/apis/resources/mgmt/management-service-add-apple-provider)
+
+use serde::{Deserialize, Serialize};
+
+use super::ProviderOptions;
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+/// The structure of the Apple idp to be created or updated
+pub(crate) struct NewAppleIdp {
+    name: Option<String>,
+    client_id: Option<String>,
+    team_id: Option<String>,
+    key_id: Option<String>,
+    /// The base64 encoded private key downloaded from Apple
+    private_key: Option<String>,
+    scopes: Option<Vec<String>>,
+    provider_options: Option<ProviderOptions>,
+}
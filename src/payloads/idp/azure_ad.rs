@@ -0,0 +1,28 @@
+//! This module contains the structure to add or update an Azure AD identity provider
+//! For more infos see [the Zitadel documentation](https://zitadel.com/docs/apis/resources/mgmt/management-service-add-azure-ad-provider)
+
+use serde::{Deserialize, Serialize};
+
+use super::ProviderOptions;
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+/// The structure of the Azure AD idp to be created or updated
+pub(crate) struct NewAzureAdIdp {
+    name: Option<String>,
+    client_id: Option<String>,
+    client_secret: Option<String>,
+    tenant: Option<AzureAdTenant>,
+    email_verified: Option<bool>,
+    scopes: Option<Vec<String>>,
+    provider_options: Option<ProviderOptions>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+/// The tenant of the Azure AD idp, either a tenant type or a tenant id
+pub(crate) struct AzureAdTenant {
+    /// Either `AZURE_AD_TENANT_TYPE_COMMON`, `AZURE_AD_TENANT_TYPE_ORGANISATIONS` or `AZURE_AD_TENANT_TYPE_CONSUMERS`
+    tenant_type: Option<String>,
+    tenant_id: Option<String>,
+}
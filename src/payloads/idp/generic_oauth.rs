@@ -0,0 +1,23 @@
+//! This module contains the structure to add or update a generic OAuth 2.0 identity provider
+//! For more infos see [the Zitadel documentation](https://zitadel.com/docs/apis/resources/mgmt/management-service-add-generic-o-auth-provider)
+
+use serde::{Deserialize, Serialize};
+
+use super::ProviderOptions;
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+/// The structure of the generic OAuth 2.0 idp to be created or updated
+pub(crate) struct NewGenericOAuthIdp {
+    name: Option<String>,
+    client_id: Option<String>,
+    client_secret: Option<String>,
+    authorization_endpoint: Option<String>,
+    token_endpoint: Option<String>,
+    user_endpoint: Option<String>,
+    scopes: Option<Vec<String>>,
+    /// The attribute of the user endpoint response used as the unique id of the user
+    id_attribute: Option<String>,
+    use_pkce: Option<bool>,
+    provider_options: Option<ProviderOptions>,
+}
@@ -0,0 +1,21 @@
+//! This module contains the structure to add or update a generic OpenID Connect identity provider
+//! For more infos see [the Zitadel documentation](https://zitadel.com/docs/apis/resources/mgmt/management-service-add-generic-oidc-provider)
+
+use serde::{Deserialize, Serialize};
+
+use super::ProviderOptions;
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+/// The structure of the generic OpenID Connect idp to be created or updated
+pub(crate) struct NewGenericOidcIdp {
+    name: Option<String>,
+    issuer: Option<String>,
+    client_id: Option<String>,
+    client_secret: Option<String>,
+    scopes: Option<Vec<String>>,
+    /// Whether the user information is taken from the id token instead of the userinfo endpoint
+    is_id_token_mapping: Option<bool>,
+    use_pkce: Option<bool>,
+    provider_options: Option<ProviderOptions>,
+}
@@ -0,0 +1,17 @@
+//! This module contains the structure to add or update a GitHub identity provider
+//! For more infos see [the Zitadel documentation](https://zitadel.com/docs/apis/resources/mgmt/management-service-add-git-hub-provider)
+
+use serde::{Deserialize, Serialize};
+
+use super::ProviderOptions;
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+/// The structure of the GitHub idp to be created or updated
+pub(crate) struct NewGitHubIdp {
+    name: Option<String>,
+    client_id: Option<String>,
+    client_secret: Option<String>,
+    scopes: Option<Vec<String>>,
+    provider_options: Option<ProviderOptions>,
+}
@@ -0,0 +1,20 @@
+//! This module contains the structure to add or update a GitHub Enterprise Server identity provider
+//! For more infos see [the Zitadel documentation](https://zitadel.com/docs/apis/resources/mgmt/management-service-add-git-hub-enterprise-server-provider)
+
+use serde::{Deserialize, Serialize};
+
+use super::ProviderOptions;
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+/// The structure of the GitHub Enterprise Server idp to be created or updated
+pub(crate) struct NewGitHubEnterpriseIdp {
+    name: Option<String>,
+    client_id: Option<String>,
+    client_secret: Option<String>,
+    authorization_endpoint: Option<String>,
+    token_endpoint: Option<String>,
+    user_endpoint: Option<String>,
+    scopes: Option<Vec<String>>,
+    provider_options: Option<ProviderOptions>,
+}
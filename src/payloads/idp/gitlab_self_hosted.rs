@@ -0,0 +1,18 @@
+//! This module contains the structure to add or update a self-hosted GitLab identity provider
+//! For more infos see [the Zitadel documentation](https://zitadel.com/docs/apis/resources/mgmt/management-service-add-git-lab-self-hosted-provider)
+
+use serde::{Deserialize, Serialize};
+
+use super::ProviderOptions;
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+/// The structure of the self-hosted GitLab idp to be created or updated
+pub(crate) struct NewGitLabSelfHostedIdp {
+    name: Option<String>,
+    issuer: Option<String>,
+    client_id: Option<String>,
+    client_secret: Option<String>,
+    scopes: Option<Vec<String>>,
+    provider_options: Option<ProviderOptions>,
+}
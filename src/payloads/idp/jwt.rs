@@ -0,0 +1,19 @@
+//! This module contains the structure to add or update a JWT identity provider
+//! For more infos see [the Zitadel documentation](https://zitadel.com/docs/apis/resources/mgmt/management-service-add-jwt-provider)
+
+use serde::{Deserialize, Serialize};
+
+use super::ProviderOptions;
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+/// The structure of the JWT idp to be created or updated
+pub(crate) struct NewJwtIdp {
+    name: Option<String>,
+    issuer: Option<String>,
+    jwt_endpoint: Option<String>,
+    keys_endpoint: Option<String>,
+    /// The header the JWT is sent in
+    header_name: Option<String>,
+    provider_options: Option<ProviderOptions>,
+}
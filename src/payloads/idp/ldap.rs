@@ -0,0 +1,47 @@
+//! This module contains the structure to add or update an LDAP identity provider
+//! For more infos see [the Zitadel documentation](https://zitadel.com/docs/apis/resources/mgmt/management-service-add-ldap-provider)
+
+use serde::{Deserialize, Serialize};
+
+use super::ProviderOptions;
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+/// The structure of the LDAP idp to be created or updated
+pub(crate) struct NewLdapIdp {
+    name: Option<String>,
+    /// e.g. `ldaps://ldap.example.com:636`
+    servers: Option<Vec<String>>,
+    start_tls: Option<bool>,
+    base_dn: Option<String>,
+    bind_dn: Option<String>,
+    bind_password: Option<String>,
+    user_base: Option<String>,
+    user_object_classes: Option<Vec<String>>,
+    user_filters: Option<Vec<String>>,
+    /// e.g. `10s`
+    timeout: Option<String>,
+    attributes: Option<LdapAttributes>,
+    /// The base64 encoded certificate of the root CA
+    root_ca: Option<String>,
+    provider_options: Option<ProviderOptions>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+/// The LDAP attributes mapped to the fields of the Zitadel users
+pub(crate) struct LdapAttributes {
+    id_attribute: Option<String>,
+    first_name_attribute: Option<String>,
+    last_name_attribute: Option<String>,
+    display_name_attribute: Option<String>,
+    nick_name_attribute: Option<String>,
+    preferred_username_attribute: Option<String>,
+    email_attribute: Option<String>,
+    email_verified_attribute: Option<String>,
+    phone_attribute: Option<String>,
+    phone_verified_attribute: Option<String>,
+    preferred_language_attribute: Option<String>,
+    avatar_url_attribute: Option<String>,
+    profile_attribute: Option<String>,
+}
@@ -1 +1,131 @@
+pub(crate) mod apple;
+pub(crate) mod azure_ad;
+pub(crate) mod generic_oauth;
+pub(crate) mod generic_oidc;
+pub(crate) mod github;
+pub(crate) mod github_enterprise;
+pub(crate) mod gitlab;
+pub(crate) mod gitlab_self_hosted;
 pub(crate) mod google;
+pub(crate) mod jwt;
+pub(crate) mod ldap;
+pub(crate) mod saml;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::Path;
+
+use crate::error::ZitadelCLIError;
+
+use super::{load_payload, load_payloads, normalize, PayloadFormat};
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+/// The options shared by every idp template, they define how the users of the idp are linked and created
+pub(crate) struct ProviderOptions {
+    is_linking_allowed: Option<bool>,
+    is_creation_allowed: Option<bool>,
+    is_auto_creation: Option<bool>,
+    is_auto_update: Option<bool>,
+    /// e.g. `AUTO_LINKING_OPTION_USERNAME` or `AUTO_LINKING_OPTION_EMAIL`
+    auto_linking: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+/// The identity provider templates
+pub(crate) enum IdpType {
+    Google,
+    GenericOidc,
+    GenericOauth,
+    Jwt,
+    AzureAd,
+    Github,
+    GithubEnterprise,
+    Gitlab,
+    GitlabSelfHosted,
+    Ldap,
+    Saml,
+    Apple,
+}
+
+impl IdpType {
+    /// Returns the path of the template in the management API
+    pub(crate) fn path(&self) -> &'static str {
+        match self {
+            IdpType::Google => "google",
+            IdpType::GenericOidc => "generic_oidc",
+            IdpType::GenericOauth => "oauth",
+            IdpType::Jwt => "jwt",
+            IdpType::AzureAd => "azure",
+            IdpType::Github => "github",
+            IdpType::GithubEnterprise => "github_es",
+            IdpType::Gitlab => "gitlab",
+            IdpType::GitlabSelfHosted => "gitlab_self_hosted",
+            IdpType::Ldap => "ldap",
+            IdpType::Saml => "saml",
+            IdpType::Apple => "apple",
+        }
+    }
+
+    /// Returns the template of the `type` of an identity provider returned by the API, e.g. `IDP_TYPE_GOOGLE`
+    pub(crate) fn from_api(idp_type: &str) -> Option<IdpType> {
+        match idp_type {
+            "IDP_TYPE_GOOGLE" => Some(IdpType::Google),
+            "IDP_TYPE_OIDC" => Some(IdpType::GenericOidc),
+            "IDP_TYPE_OAUTH" => Some(IdpType::GenericOauth),
+            "IDP_TYPE_JWT" => Some(IdpType::Jwt),
+            "IDP_TYPE_AZURE_AD" => Some(IdpType::AzureAd),
+            "IDP_TYPE_GITHUB" => Some(IdpType::Github),
+            "IDP_TYPE_GITHUB_ES" => Some(IdpType::GithubEnterprise),
+            "IDP_TYPE_GITLAB" => Some(IdpType::Gitlab),
+            "IDP_TYPE_GITLAB_SELF_HOSTED" => Some(IdpType::GitlabSelfHosted),
+            "IDP_TYPE_LDAP" => Some(IdpType::Ldap),
+            "IDP_TYPE_SAML" => Some(IdpType::Saml),
+            "IDP_TYPE_APPLE" => Some(IdpType::Apple),
+            _ => None,
+        }
+    }
+
+    /// Checks `spec` against the payload of the template, see [`normalize`]
+    pub(crate) fn normalize(&self, spec: Value) -> Result<Value, ZitadelCLIError> {
+        match self {
+            IdpType::Google => normalize::<google::NewGoogleIdp>(spec),
+            IdpType::GenericOidc => normalize::<generic_oidc::NewGenericOidcIdp>(spec),
+            IdpType::GenericOauth => normalize::<generic_oauth::NewGenericOAuthIdp>(spec),
+            IdpType::Jwt => normalize::<jwt::NewJwtIdp>(spec),
+            IdpType::AzureAd => normalize::<azure_ad::NewAzureAdIdp>(spec),
+            IdpType::Github => normalize::<github::NewGitHubIdp>(spec),
+            IdpType::GithubEnterprise => {
+                normalize::<github_enterprise::NewGitHubEnterpriseIdp>(spec)
+            }
+            IdpType::Gitlab => normalize::<gitlab::NewGitLabIdp>(spec),
+            IdpType::GitlabSelfHosted => {
+                normalize::<gitlab_self_hosted::NewGitLabSelfHostedIdp>(spec)
+            }
+            IdpType::Ldap => normalize::<ldap::NewLdapIdp>(spec),
+            IdpType::Saml => normalize::<saml::NewSamlIdp>(spec),
+            IdpType::Apple => normalize::<apple::NewAppleIdp>(spec),
+        }
+    }
+    /// Loads the payloads of `path` and checks them against the payload of the template, see [`load_payloads`]
+    pub(crate) fn load_payloads(
+        &self,
+        path: &Path,
+        format: Option<PayloadFormat>,
+    ) -> Result<Vec<Value>, ZitadelCLIError> {
+        load_payloads::<Value>(path, format)?
+            .into_iter()
+            .map(|spec| self.normalize(spec))
+            .collect()
+    }
+
+    /// Loads the single payload of `path` and checks it against the payload of the template, see [`load_payload`]
+    pub(crate) fn load_payload(
+        &self,
+        path: &Path,
+        format: Option<PayloadFormat>,
+    ) -> Result<Value, ZitadelCLIError> {
+        self.normalize(load_payload::<Value>(path, format)?)
+    }
+}
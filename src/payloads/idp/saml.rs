@@ -0,0 +1,23 @@
+//! This module contains the structure to add or update a SAML identity provider
+//! For more infos see [the Zitadel documentation](https://zitadel.com/docs/apis/resources/mgmt/management-service-add-saml-provider)
+
+use serde::{Deserialize, Serialize};
+
+use super::ProviderOptions;
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+/// The structure of the SAML idp to be created or updated
+pub(crate) struct NewSamlIdp {
+    name: Option<String>,
+    /// The base64 encoded metadata of the identity provider, either this or `metadataUrl` is required
+    metadata_xml: Option<String>,
+    metadata_url: Option<String>,
+    /// e.g. `SAML_BINDING_POST` or `SAML_BINDING_REDIRECT`
+    binding: Option<String>,
+    with_signed_request: Option<bool>,
+    /// e.g. `SAML_NAME_ID_FORMAT_PERSISTENT`
+    name_id_format: Option<String>,
+    transient_mapping_attribute_name: Option<String>,
+    provider_options: Option<ProviderOptions>,
+}
@@ -0,0 +1,334 @@
+//! This module contains the manifests describing the desired configuration of organizations, see `apply`
+//! A manifest is a YAML document whose `kind` selects the resource, several manifests can be separated by `---`
+//! The resources reference each other by name: `org` is the name of the organization, the one targeted by `--org`
+//! if missing, and `project` the name of the project
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::error::ZitadelCLIError;
+
+use super::{
+    action::NewAction,
+    application::ApplicationType,
+    idp::IdpType,
+    member::NewMember,
+    organization::NewOrganization,
+    parse_yaml_documents,
+    policy::PolicyType,
+    project::{NewProject, ProjectRole},
+};
+
+/// The fields the API never returns, they can't be compared with the live configuration
+pub(crate) const SECRET_FIELDS: [&str; 3] = ["clientSecret", "bindPassword", "privateKey"];
+/// The value replacing the secrets in the exported manifests
+pub(crate) const REDACTED: &str = "REDACTED";
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// The kinds of resources, in the order they are created
+pub(crate) enum Kind {
+    Organization,
+    Idp,
+    Policy,
+    Action,
+    Project,
+    ProjectRole,
+    App,
+    ProjectGrant,
+    Member,
+}
+
+impl Kind {
+    /// Returns the name of the kind in the plan
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            Kind::Organization => "organization",
+            Kind::Idp => "idp",
+            Kind::Policy => "policy",
+            Kind::Action => "action",
+            Kind::Project => "project",
+            Kind::ProjectRole => "project-role",
+            Kind::App => "app",
+            Kind::ProjectGrant => "project-grant",
+            Kind::Member => "member",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(tag = "kind", rename_all_fields = "camelCase")]
+/// A resource of the desired configuration
+pub(crate) enum Manifest {
+    /// An organization, created if missing. Its admins are only set at creation
+    Organization { spec: NewOrganization },
+    /// An identity provider of the organization, `type` selects its template
+    Idp {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        org: Option<String>,
+        #[serde(rename = "type")]
+        idp_type: IdpType,
+        spec: Value,
+    },
+    /// A policy of the organization, `type` selects the policy. Without manifest, the policy of the instance is used
+    Policy {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        org: Option<String>,
+        #[serde(rename = "type")]
+        policy_type: PolicyType,
+        spec: Value,
+    },
+    Action {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        org: Option<String>,
+        spec: NewAction,
+    },
+    Project {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        org: Option<String>,
+        spec: NewProject,
+    },
+    /// A role of a project, identified by its key
+    ProjectRole {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        org: Option<String>,
+        project: String,
+        spec: ProjectRole,
+    },
+    /// An application of a project, `type` can't be changed once it is created
+    App {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        org: Option<String>,
+        project: String,
+        #[serde(rename = "type")]
+        app_type: ApplicationType,
+        spec: Value,
+    },
+    /// A grant of a project to another organization, identified by the name of the granted organization
+    ProjectGrant {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        org: Option<String>,
+        project: String,
+        granted_org: String,
+        role_keys: Vec<String>,
+    },
+    /// A member of the organization, or of `project` if set, identified by its user id
+    Member {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        org: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        project: Option<String>,
+        spec: NewMember,
+    },
+}
+
+impl Manifest {
+    pub(crate) fn kind(&self) -> Kind {
+        match self {
+            Manifest::Organization { .. } => Kind::Organization,
+            Manifest::Idp { .. } => Kind::Idp,
+            Manifest::Policy { .. } => Kind::Policy,
+            Manifest::Action { .. } => Kind::Action,
+            Manifest::Project { .. } => Kind::Project,
+            Manifest::ProjectRole { .. } => Kind::ProjectRole,
+            Manifest::App { .. } => Kind::App,
+            Manifest::ProjectGrant { .. } => Kind::ProjectGrant,
+            Manifest::Member { .. } => Kind::Member,
+        }
+    }
+
+    /// Returns the name of the organization the resource belongs to, `None` for the one targeted by `--org`
+    pub(crate) fn org(&self) -> Option<&str> {
+        match self {
+            Manifest::Organization { .. } => None,
+            Manifest::Idp { org, .. }
+            | Manifest::Policy { org, .. }
+            | Manifest::Action { org, .. }
+            | Manifest::Project { org, .. }
+            | Manifest::ProjectRole { org, .. }
+            | Manifest::App { org, .. }
+            | Manifest::ProjectGrant { org, .. }
+            | Manifest::Member { org, .. } => org.as_deref(),
+        }
+    }
+
+    /// Returns the name of the project the resource belongs to
+    pub(crate) fn project(&self) -> Option<&str> {
+        match self {
+            Manifest::ProjectRole { project, .. }
+            | Manifest::App { project, .. }
+            | Manifest::ProjectGrant { project, .. } => Some(project),
+            Manifest::Member { project, .. } => project.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Returns the name identifying the resource among the resources of its kind and project
+    pub(crate) fn name(&self) -> String {
+        match self {
+            Manifest::Organization { spec } => spec.name.clone(),
+            Manifest::Project { spec, .. } => spec.name.clone(),
+            Manifest::ProjectRole { spec, .. } => spec.key.clone(),
+            Manifest::Policy { policy_type, .. } => policy_type.as_str().to_string(),
+            Manifest::Action { spec, .. } => spec.name.clone(),
+            Manifest::Member { spec, .. } => spec.user_id.clone(),
+            Manifest::Idp { spec, .. } | Manifest::App { spec, .. } => spec
+                .get("name")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string(),
+            Manifest::ProjectGrant { granted_org, .. } => granted_org.clone(),
+        }
+    }
+
+    /// Returns the resource as shown in the plan, e.g. `app shop/web`
+    pub(crate) fn label(&self) -> String {
+        label(self.kind(), self.org(), self.project(), &self.name())
+    }
+
+    /// Returns the checked specification of the resource, as sent to the API
+    /// - Returns `Err(ZitadelCLIError::Manifest(error))` if the specification doesn't match the payload of the resource
+    pub(crate) fn spec(&self) -> Result<Value, ZitadelCLIError> {
+        let spec = match self {
+            Manifest::Organization { spec } => serde_json::to_value(spec).map_err(Into::into),
+            Manifest::Project { spec, .. } => serde_json::to_value(spec).map_err(Into::into),
+            Manifest::ProjectRole { spec, .. } => serde_json::to_value(spec).map_err(Into::into),
+            Manifest::Action { spec, .. } => serde_json::to_value(spec).map_err(Into::into),
+            Manifest::Member { spec, .. } => serde_json::to_value(spec).map_err(Into::into),
+            Manifest::Policy {
+                policy_type, spec, ..
+            } => policy_type.normalize(spec.clone()),
+            Manifest::Idp { idp_type, spec, .. } => idp_type.normalize(spec.clone()),
+            Manifest::App { app_type, spec, .. } => app_type.normalize(spec.clone()),
+            Manifest::ProjectGrant { role_keys, .. } => Ok(json!({ "roleKeys": role_keys })),
+        };
+        spec.map_err(|error| ZitadelCLIError::Manifest(format!("{}: {error}", self.label())))
+    }
+}
+
+/// Replaces the secrets supported by the payload of `spec` by [`REDACTED`]
+pub(crate) fn redact(spec: &mut Value) {
+    let Some(spec) = spec.as_object_mut() else {
+        return;
+    };
+    for field in SECRET_FIELDS {
+        if let Some(value) = spec.get_mut(field) {
+            *value = Value::String(REDACTED.to_string());
+        }
+    }
+}
+
+/// Returns the fields of `spec` whose secret was redacted by `export`
+pub(crate) fn redacted_fields(spec: &Value) -> Vec<&str> {
+    SECRET_FIELDS
+        .into_iter()
+        .filter(|field| spec.get(field).and_then(Value::as_str) == Some(REDACTED))
+        .collect()
+}
+
+/// Returns a resource as shown in the plan, its organization and project are omitted when missing
+pub(crate) fn label(kind: Kind, org: Option<&str>, project: Option<&str>, name: &str) -> String {
+    let path = [org, project, Some(name)]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join("/");
+    format!("{} {path}", kind.as_str())
+}
+
+/// Loads the manifests of a YAML file, or of every `.yaml` and `.yml` file of a directory in alphabetical order
+/// - Returns `Err(ZitadelCLIError::Manifest(error))` with the file of the first invalid manifest
+pub(crate) fn load_manifests(path: &Path) -> Result<Vec<Manifest>, ZitadelCLIError> {
+    let mut paths: Vec<PathBuf> = match path.is_dir() {
+        true => fs::read_dir(path)?
+            .map(|entry| entry.map(|entry| entry.path()))
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .filter(|path| {
+                path.extension()
+                    .is_some_and(|extension| extension == "yaml" || extension == "yml")
+            })
+            .collect(),
+        false => vec![path.to_path_buf()],
+    };
+    paths.sort();
+    let mut manifests = vec![];
+    for path in paths {
+        let content = fs::read_to_string(&path)?;
+        manifests.extend(
+            parse_yaml_documents::<Manifest>(&content).map_err(|error| {
+                ZitadelCLIError::Manifest(format!("{}: {error}", path.display()))
+            })?,
+        );
+    }
+    Ok(manifests)
+}
+
+/// Writes the manifests to a multi-document YAML file, without the `null` fields
+pub(crate) fn write_manifests(path: &Path, manifests: &[Manifest]) -> Result<(), ZitadelCLIError> {
+    let documents = manifests
+        .iter()
+        .map(|manifest| {
+            let mut document = serde_yaml::to_value(manifest)?;
+            remove_nulls(&mut document);
+            serde_yaml::to_string(&document)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    fs::write(path, documents.join("---\n"))?;
+    Ok(())
+}
+
+fn remove_nulls(value: &mut serde_yaml::Value) {
+    match value {
+        serde_yaml::Value::Mapping(mapping) => {
+            mapping.retain(|_, value| !value.is_null());
+            mapping.values_mut().for_each(remove_nulls);
+        }
+        serde_yaml::Value::Sequence(sequence) => sequence.iter_mut().for_each(remove_nulls),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_manifests() {
+        let manifests = load_manifests(Path::new("examples/manifests")).unwrap();
+        let labels = manifests.iter().map(Manifest::label).collect::<Vec<_>>();
+        assert_eq!(
+            labels,
+            [
+                "organization partner",
+                "idp Google",
+                "project shop",
+                "project-role shop/editor",
+                "project-role shop/viewer",
+                "app shop/web",
+                "app shop/api",
+                "project-grant shop/partner"
+            ]
+        );
+        assert_eq!(
+            manifests[5].spec().unwrap()["redirectUris"],
+            json!(["https://shop.example.com/callback"])
+        );
+
+        let error =
+            parse_yaml_documents::<Manifest>("kind: Project\nspec:\n  nme: shop\n").unwrap_err();
+        assert!(error.to_string().contains("missing field `name`"));
+    }
+
+    #[test]
+    fn test_redact() {
+        let mut spec = json!({ "name": "Google", "clientId": "id", "clientSecret": "secret" });
+        redact(&mut spec);
+        assert_eq!(spec["clientSecret"], REDACTED);
+        assert_eq!(redacted_fields(&spec), ["clientSecret"]);
+    }
+}
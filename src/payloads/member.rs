@@ -0,0 +1,13 @@
+//! This module contains the structure of the members, the users with administrative roles
+//! For more infos see [the Zitadel documentation](https://zitadel.com/docs/apis/resources/mgmt/management-service-add-org-member)
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+/// The structure of the member to be added or updated
+pub(crate) struct NewMember {
+    pub(crate) user_id: String,
+    /// e.g. `ORG_OWNER` or `PROJECT_OWNER`
+    roles: Vec<String>,
+}
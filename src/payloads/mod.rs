@@ -1,20 +1,137 @@
+pub mod action;
+pub mod application;
+pub mod hashed_password;
 pub mod idp;
+pub mod manifest;
+pub mod member;
 pub mod organization;
+pub mod policy;
 pub mod project;
 pub mod user;
 
-use std::{fs::File, io::BufReader, path::Path};
+use std::{
+    fs,
+    io::{self, Read},
+    path::Path,
+};
+
+use clap::ValueEnum;
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::Value;
 
 use crate::error::ZitadelCLIError;
 
-pub fn load_from_file<T>(path: &Path) -> Result<T, ZitadelCLIError>
+/// The path reading the payload from the standard input
+const STDIN: &str = "-";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+/// The formats the payloads can be written in
+pub(crate) enum PayloadFormat {
+    Json,
+    /// Several payloads can be separated by `---`
+    Yaml,
+    Toml,
+}
+
+impl PayloadFormat {
+    /// Returns the format matching the extension of `path`, JSON if it has another extension or none
+    fn from_path(path: &Path) -> PayloadFormat {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("yaml" | "yml") => PayloadFormat::Yaml,
+            Some("toml") => PayloadFormat::Toml,
+            _ => PayloadFormat::Json,
+        }
+    }
+}
+
+/// Loads the payloads of a file, or of the standard input if `path` is `-`
+/// The format is `format` if specified, the one of the extension of the file otherwise, JSON by default
+/// - Returns `Err(ZitadelCLIError::Payload(error))` if the file holds no payload or a payload doesn't match `T`
+pub(crate) fn load_payloads<T>(
+    path: &Path,
+    format: Option<PayloadFormat>,
+) -> Result<Vec<T>, ZitadelCLIError>
+where
+    T: DeserializeOwned,
+{
+    let content = match path.as_os_str() == STDIN {
+        true => {
+            let mut content = String::new();
+            io::stdin().read_to_string(&mut content)?;
+            content
+        }
+        false => fs::read_to_string(path)?,
+    };
+    let payloads = match format.unwrap_or_else(|| PayloadFormat::from_path(path)) {
+        PayloadFormat::Json => serde_json::from_str(&content)
+            .map(|payload| vec![payload])
+            .map_err(|error| error.to_string()),
+        PayloadFormat::Yaml => parse_yaml_documents(&content).map_err(|error| error.to_string()),
+        PayloadFormat::Toml => toml::from_str(&content)
+            .map(|payload| vec![payload])
+            .map_err(|error| error.to_string()),
+    };
+    match payloads {
+        Ok(payloads) if payloads.is_empty() => Err(ZitadelCLIError::Payload(format!(
+            "{} holds no payload",
+            source(path)
+        ))),
+        Ok(payloads) => Ok(payloads),
+        Err(error) => Err(ZitadelCLIError::Payload(format!(
+            "{}: {error}",
+            source(path)
+        ))),
+    }
+}
+
+/// Loads the payload of a file like [`load_payloads`], for the commands taking a single payload
+/// - Returns `Err(ZitadelCLIError::Payload(error))` if the file holds several payloads
+pub(crate) fn load_payload<T>(
+    path: &Path,
+    format: Option<PayloadFormat>,
+) -> Result<T, ZitadelCLIError>
+where
+    T: DeserializeOwned,
+{
+    let mut payloads = load_payloads(path, format)?;
+    match payloads.len() {
+        1 => Ok(payloads.remove(0)),
+        count => Err(ZitadelCLIError::Payload(format!(
+            "{} holds {count} payloads, the command takes a single one",
+            source(path)
+        ))),
+    }
+}
+
+/// Returns the name of the file of the payloads in the errors
+fn source(path: &Path) -> String {
+    match path.as_os_str() == STDIN {
+        true => "the standard input".to_string(),
+        false => path.display().to_string(),
+    }
+}
+
+/// Parses the documents of a multi-document YAML file, separated by `---`, the empty documents are skipped
+pub(crate) fn parse_yaml_documents<T>(content: &str) -> Result<Vec<T>, serde_yaml::Error>
 where
-    T: serde::de::DeserializeOwned,
+    T: DeserializeOwned,
 {
-    let file = File::open(path)?;
-    let reader = BufReader::new(file);
-    let payload: T = serde_json::from_reader(reader)?;
-    Ok(payload)
+    serde_yaml::Deserializer::from_str(content)
+        .map(serde_yaml::Value::deserialize)
+        .filter(|document| !matches!(document, Ok(serde_yaml::Value::Null)))
+        .map(|document| serde_yaml::from_value(document?))
+        .collect()
+}
+
+/// Checks that `value` matches the payload `T` and returns it as sent to the API, the missing fields set to `null`
+/// - Returns `Err(ZitadelCLIError::Payload(error))` if `value` doesn't match the payload
+pub(crate) fn normalize<T>(value: Value) -> Result<Value, ZitadelCLIError>
+where
+    T: serde::de::DeserializeOwned + serde::Serialize,
+{
+    let payload = serde_json::from_value::<T>(value)
+        .map_err(|error| ZitadelCLIError::Payload(error.to_string()))?;
+    Ok(serde_json::to_value(payload)?)
 }
 
 #[cfg(test)]
@@ -25,8 +142,22 @@ mod tests {
 
     #[test]
     /// TODO: Compare the content of the user loaded from the file with the expected user
-    fn test_load_from_file() {
+    fn test_load_payloads() {
         let path = Path::new("examples/add_user.json");
-        load_from_file::<NewHumanUser>(path).unwrap();
+        load_payload::<NewHumanUser>(path, None).unwrap();
+
+        let users =
+            load_payloads::<NewHumanUser>(Path::new("examples/add_users.yaml"), None).unwrap();
+        assert_eq!(users.len(), 2);
+        let user = load_payload::<NewHumanUser>(Path::new("examples/add_user.toml"), None).unwrap();
+        assert_eq!(
+            serde_json::to_value(user).unwrap()["profile"]["givenName"],
+            "Minnie"
+        );
+
+        let error = load_payload::<NewHumanUser>(Path::new("examples/add_users.yaml"), None);
+        assert!(matches!(error, Err(ZitadelCLIError::Payload(_))));
+        let error = load_payload::<NewHumanUser>(path, Some(PayloadFormat::Toml));
+        assert!(matches!(error, Err(ZitadelCLIError::Payload(_))));
     }
 }
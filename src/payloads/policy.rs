@@ -0,0 +1,106 @@
+//! This module contains the structures of the policies of an organization, an organization uses the default
+//! policy of the instance until it has its own
+//! For more infos see [the Zitadel documentation](https://zitadel.com/docs/apis/resources/mgmt/policies)
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::error::ZitadelCLIError;
+
+use super::normalize;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+/// The policies of an organization that can be customized
+pub(crate) enum PolicyType {
+    Login,
+    PasswordComplexity,
+    Lockout,
+}
+
+impl PolicyType {
+    pub(crate) const ALL: [PolicyType; 3] = [
+        PolicyType::Login,
+        PolicyType::PasswordComplexity,
+        PolicyType::Lockout,
+    ];
+
+    pub(crate) fn as_str(&self) -> &'static str {
+        match self {
+            PolicyType::Login => "login",
+            PolicyType::PasswordComplexity => "password-complexity",
+            PolicyType::Lockout => "lockout",
+        }
+    }
+
+    /// Returns the path of the policy in the management API
+    pub(crate) fn path(&self) -> &'static str {
+        match self {
+            PolicyType::Login => "login",
+            PolicyType::PasswordComplexity => "password/complexity",
+            PolicyType::Lockout => "lockout",
+        }
+    }
+
+    /// Checks `spec` against the payload of the policy, see [`normalize`]
+    pub(crate) fn normalize(&self, spec: Value) -> Result<Value, ZitadelCLIError> {
+        match self {
+            PolicyType::Login => normalize::<LoginPolicy>(spec),
+            PolicyType::PasswordComplexity => normalize::<PasswordComplexityPolicy>(spec),
+            PolicyType::Lockout => normalize::<LockoutPolicy>(spec),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+/// How the users of the organization log in
+/// For more details about each field, you can look at the [documentation](https://zitadel.com/docs/apis/resources/mgmt/management-service-add-custom-login-policy)
+pub(crate) struct LoginPolicy {
+    allow_username_password: Option<bool>,
+    allow_register: Option<bool>,
+    allow_external_idp: Option<bool>,
+    force_mfa: Option<bool>,
+    force_mfa_local_only: Option<bool>,
+    /// Either `PASSWORDLESS_TYPE_NOT_ALLOWED` or `PASSWORDLESS_TYPE_ALLOWED`
+    passwordless_type: Option<String>,
+    hide_password_reset: Option<bool>,
+    ignore_unknown_usernames: Option<bool>,
+    allow_domain_discovery: Option<bool>,
+    disable_login_with_email: Option<bool>,
+    disable_login_with_phone: Option<bool>,
+    default_redirect_uri: Option<String>,
+    /// e.g. `864000s`
+    password_check_lifetime: Option<String>,
+    external_login_check_lifetime: Option<String>,
+    mfa_init_skip_lifetime: Option<String>,
+    second_factor_check_lifetime: Option<String>,
+    multi_factor_check_lifetime: Option<String>,
+    /// e.g. `SECOND_FACTOR_TYPE_OTP`, `SECOND_FACTOR_TYPE_U2F`
+    second_factors: Option<Vec<String>>,
+    /// e.g. `MULTI_FACTOR_TYPE_U2F_WITH_VERIFICATION`
+    multi_factors: Option<Vec<String>>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+/// The requirements of the passwords
+/// For more details about each field, you can look at the [documentation](https://zitadel.com/docs/apis/resources/mgmt/management-service-add-custom-password-complexity-policy)
+pub(crate) struct PasswordComplexityPolicy {
+    /// The API encodes this 64 bits integer as a string
+    min_length: Option<String>,
+    has_uppercase: Option<bool>,
+    has_lowercase: Option<bool>,
+    has_number: Option<bool>,
+    has_symbol: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+/// The number of failed attempts after which a user is locked
+/// For more details about each field, you can look at the [documentation](https://zitadel.com/docs/apis/resources/mgmt/management-service-add-custom-lockout-policy)
+pub(crate) struct LockoutPolicy {
+    /// The API encodes this 64 bits integer as a string, `0` disables the lockout
+    max_password_attempts: Option<String>,
+    max_otp_attempts: Option<String>,
+}
@@ -1,13 +1,30 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Debug)]
 /// A Project is a vessel for different applications sharing the same role context.
 /// For more details about each field, you can look at the [documentation](https://zitadel.com/docs/apis/resources/mgmt/management-service-add-project)
 #[serde(rename_all = "camelCase")]
 pub(crate) struct NewProject {
-    name: String,
+    pub(crate) name: String,
     project_role_assertion: Option<bool>,
     project_role_check: Option<bool>,
     has_project_check: Option<bool>,
     private_labeling_setting: Option<String>,
 }
+
+#[derive(Serialize, Deserialize, Debug)]
+/// A role of a project, granted to users through user grants
+/// For more details about each field, you can look at the [documentation](https://zitadel.com/docs/apis/resources/mgmt/management-service-add-project-role)
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ProjectRole {
+    pub(crate) key: String,
+    display_name: String,
+    group: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+/// The roles added at once to a project
+/// For more details, you can look at the [documentation](https://zitadel.com/docs/apis/resources/mgmt/management-service-bulk-add-project-roles)
+pub(crate) struct BulkProjectRoles {
+    roles: Vec<ProjectRole>,
+}
@@ -103,3 +103,36 @@ pub struct IDPLink {
     user_id: Option<String>,
     user_name: Option<String>,
 }
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+/// The fields of a human user to update, the fields that aren't specified are left unchanged
+/// For more infos see [the Zitadel documentation](https://zitadel.com/docs/apis/resources/user_service_v2/user-service-update-human-user)
+pub struct UpdateHumanUser {
+    username: Option<String>,
+    profile: Option<Profile>,
+    email: Option<Email>,
+    phone: Option<Phone>,
+    password: Option<SetPassword>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+/// The new password of a human user, either the current password or a verification code is required
+pub struct SetPassword {
+    password: Password,
+    current_password: Option<String>,
+    verification_code: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+/// The structure of the machine (service) user to be created
+/// For more infos see [the Zitadel documentation](https://zitadel.com/docs/apis/resources/mgmt/management-service-add-machine-user)
+pub struct NewMachineUser {
+    user_name: String,
+    name: String,
+    description: Option<String>,
+    /// Either `ACCESS_TOKEN_TYPE_BEARER` or `ACCESS_TOKEN_TYPE_JWT`
+    access_token_type: Option<String>,
+}